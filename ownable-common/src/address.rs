@@ -0,0 +1,204 @@
+use blake2::{Blake2b, digest::consts::U32};
+use cosmwasm_std::Addr;
+use sha2::{Digest as _, Sha256};
+use sha3::{Digest as _, Keccak256};
+use crate::error::ContractError;
+
+type Blake2b256 = Blake2b<U32>;
+
+pub const LTO_ADDRESS_VERSION: u8 = 1;
+const LTO_ADDRESS_LENGTH: usize = 26;
+pub const LTO_CHECKSUM_LENGTH: usize = 4;
+const ETH_ADDRESS_LENGTH: usize = 40;
+const BECH32_CHARSET: &str = "qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+const BECH32_CHECKSUM_LENGTH: usize = 6;
+
+/// Validate an LTO, Ethereum or Cosmos address.
+/// Ethereum addresses are returned in their EIP-55 checksummed form.
+pub fn validate_address(address: &str, network_id: u8) -> Result<Addr, ContractError> {
+    if address.starts_with("0x") {
+        validate_eth_address(address).map(Addr::unchecked)
+    } else if is_bech32(address) {
+        validate_cosmos_address(address)?;
+        Ok(Addr::unchecked(address))
+    } else {
+        validate_lto_address(address, network_id)?;
+        Ok(Addr::unchecked(address))
+    }
+}
+
+/// Compare two addresses, the case of Ethereum addresses is ignored
+pub fn same_address(a: impl AsRef<str>, b: impl AsRef<str>) -> bool {
+    let (a, b) = (a.as_ref(), b.as_ref());
+    if a.starts_with("0x") && b.starts_with("0x") {
+        a.eq_ignore_ascii_case(b)
+    } else {
+        a == b
+    }
+}
+
+fn validate_lto_address(address: &str, network_id: u8) -> Result<(), ContractError> {
+    let bytes = bs58::decode(address)
+        .into_vec()
+        .map_err(|_| invalid_address(address, "not base58 encoded"))?;
+
+    if bytes.len() != LTO_ADDRESS_LENGTH {
+        return Err(invalid_address(address, "wrong length"));
+    }
+    if bytes[0] != LTO_ADDRESS_VERSION {
+        return Err(invalid_address(address, "unknown address version"));
+    }
+    if bytes[1] != network_id {
+        return Err(invalid_address(address, "wrong network"));
+    }
+
+    let (body, checksum) = bytes.split_at(LTO_ADDRESS_LENGTH - LTO_CHECKSUM_LENGTH);
+    if secure_hash(body)[..LTO_CHECKSUM_LENGTH] != *checksum {
+        return Err(invalid_address(address, "checksum mismatch"));
+    }
+    Ok(())
+}
+
+// sha256(blake2b256(data)), used by LTO for address checksums
+pub fn secure_hash(data: &[u8]) -> Vec<u8> {
+    Sha256::digest(Blake2b256::digest(data)).to_vec()
+}
+
+/// Check the format of an Ethereum address, ignoring the checksum
+pub fn is_eth_address(address: &str) -> bool {
+    address.strip_prefix("0x").is_some_and(|digits| {
+        digits.len() == ETH_ADDRESS_LENGTH && digits.chars().all(|c| c.is_ascii_hexdigit())
+    })
+}
+
+fn validate_eth_address(address: &str) -> Result<String, ContractError> {
+    if !is_eth_address(address) {
+        return Err(invalid_address(address, "expected 20 hex encoded bytes"));
+    }
+    let digits = &address[2..];
+
+    let checksummed = eip55_checksum(digits);
+
+    // an all lower or all upper case address carries no checksum
+    let mixed_case = digits.chars().any(|c| c.is_ascii_lowercase())
+        && digits.chars().any(|c| c.is_ascii_uppercase());
+    if mixed_case && checksummed[2..] != *digits {
+        return Err(invalid_address(address, "checksum mismatch"));
+    }
+    Ok(checksummed)
+}
+
+/// Hex address with the EIP-55 mixed case checksum, prefixed with 0x
+pub fn eip55_checksum(digits: &str) -> String {
+    let lower = digits.to_ascii_lowercase();
+    let hash = Keccak256::digest(lower.as_bytes());
+
+    let mut address = String::from("0x");
+    for (i, c) in lower.chars().enumerate() {
+        let nibble = if i % 2 == 0 { hash[i / 2] >> 4 } else { hash[i / 2] & 0x0f };
+        if c.is_ascii_alphabetic() && nibble >= 8 {
+            address.push(c.to_ascii_uppercase());
+        } else {
+            address.push(c);
+        }
+    }
+    address
+}
+
+// cosmos addresses are bech32 encoded, with a lower case human readable prefix
+fn is_bech32(address: &str) -> bool {
+    matches!(
+        address.rsplit_once('1'),
+        Some((hrp, _)) if !hrp.is_empty() && hrp.chars().all(|c| c.is_ascii_lowercase())
+    )
+}
+
+/// Validate a bech32 encoded Cosmos address
+pub fn validate_cosmos_address(address: &str) -> Result<(), ContractError> {
+    let (hrp, data) = address.rsplit_once('1')
+        .ok_or_else(|| invalid_address(address, "missing bech32 separator"))?;
+    if hrp.is_empty() || data.len() < BECH32_CHECKSUM_LENGTH {
+        return Err(invalid_address(address, "wrong length"));
+    }
+
+    let values = data.chars()
+        .map(|c| BECH32_CHARSET.find(c).map(|i| i as u8))
+        .collect::<Option<Vec<u8>>>()
+        .ok_or_else(|| invalid_address(address, "not bech32 encoded"))?;
+
+    let mut checked: Vec<u8> = hrp.bytes().map(|b| b >> 5).collect();
+    checked.push(0);
+    checked.extend(hrp.bytes().map(|b| b & 0x1f));
+    checked.extend(values);
+    if bech32_polymod(&checked) != 1 {
+        return Err(invalid_address(address, "checksum mismatch"));
+    }
+    Ok(())
+}
+
+fn bech32_polymod(values: &[u8]) -> u32 {
+    const GENERATOR: [u32; 5] = [0x3b6a57b2, 0x26508e6d, 0x1ea119fa, 0x3d4233dd, 0x2a1462b3];
+
+    let mut checksum: u32 = 1;
+    for value in values {
+        let top = checksum >> 25;
+        checksum = ((checksum & 0x1ffffff) << 5) ^ *value as u32;
+        for (i, generator) in GENERATOR.iter().enumerate() {
+            if (top >> i) & 1 == 1 {
+                checksum ^= generator;
+            }
+        }
+    }
+    checksum
+}
+
+fn invalid_address(address: &str, reason: &str) -> ContractError {
+    ContractError::InvalidAddress {
+        val: format!("{}: {}", address, reason),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LTO_ADDRESS: &str = "3NBd71MErsjwmStnj8PQECHP1JL2jvuY2HW";
+    const ETH_ADDRESS: &str = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed";
+    const COSMOS_ADDRESS: &str = "cosmos1hsk6jryyqjfhp5dhc55tc9jtckygx0eph6dd02";
+
+    #[test]
+    fn test_validate_lto_address() {
+        assert_eq!(validate_address(LTO_ADDRESS, b'T').unwrap(), LTO_ADDRESS);
+
+        assert!(validate_address(LTO_ADDRESS, b'L').is_err());
+        assert!(validate_address("3NBd71MErsjwmStnj8PQECHP1JL2jvuY2HX", b'T').is_err());
+        assert!(validate_address("3NBd71MErsjwmStnj8PQECHP1JL2jvuY2H", b'T').is_err());
+        assert!(validate_address("3NBd71MErsjwmStnj8PQECHP1JL2jvuY2H0", b'T').is_err());
+    }
+
+    #[test]
+    fn test_validate_eth_address() {
+        assert_eq!(validate_address(ETH_ADDRESS, b'T').unwrap(), ETH_ADDRESS);
+        assert_eq!(validate_address(&ETH_ADDRESS.to_ascii_lowercase(), b'T').unwrap(), ETH_ADDRESS);
+
+        assert!(validate_address("0x5AAeb6053F3E94C9b9A09f33669435E7Ef1BeAed", b'T').is_err());
+        assert!(validate_address("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAe", b'T').is_err());
+        assert!(validate_address("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeg", b'T').is_err());
+    }
+
+    #[test]
+    fn test_validate_cosmos_address() {
+        assert_eq!(validate_address(COSMOS_ADDRESS, b'T').unwrap(), COSMOS_ADDRESS);
+
+        assert!(validate_address("cosmos1hsk6jryyqjfhp5dhc55tc9jtckygx0eph6dd03", b'T').is_err());
+        assert!(validate_address("cosmos1hsk6jryyqjfhp5dhc55tc9jtckygx0eph6ddb2", b'T').is_err());
+        assert!(validate_cosmos_address("cosmos1qqqq").is_err());
+    }
+
+    #[test]
+    fn test_same_address() {
+        assert!(same_address(ETH_ADDRESS, ETH_ADDRESS.to_ascii_lowercase()));
+        assert!(same_address(LTO_ADDRESS, LTO_ADDRESS));
+        assert!(!same_address(COSMOS_ADDRESS, COSMOS_ADDRESS.to_ascii_uppercase()));
+    }
+}
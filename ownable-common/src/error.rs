@@ -15,6 +15,18 @@ pub enum ContractError {
     #[error("Lock error: {val:?}")]
     LockError { val: String },
 
+    #[error("Event already processed: {val:?}")]
+    DuplicateEvent { val: String },
+
+    #[error("Invalid NFT identifier: {val:?}")]
+    InvalidAssetId { val: String },
+
+    #[error("Invalid address: {val:?}")]
+    InvalidAddress { val: String },
+
+    #[error("Transfer policy error: {val:?}")]
+    TransferPolicyError { val: String },
+
     #[error("Unknown event type: {val:?}")]
     MatchEventError { val: String },
 
@@ -24,6 +36,18 @@ pub enum ContractError {
     #[error("Invalid external event args")]
     InvalidExternalEventArgs {},
 
+    #[error("Unsupported event version: {version:?}")]
+    UnsupportedEventVersion { version: u8 },
+
+    #[error("Potion is empty")]
+    PotionEmpty {},
+
+    #[error("Invalid metadata: {val:?}")]
+    InvalidMetadata { val: String },
+
+    #[error("Ownable is burned")]
+    Burned {},
+
     #[error("Method is not implemented for this Ownable")]
     NotImplemented {},
 }
@@ -0,0 +1,290 @@
+use std::fmt;
+use std::str::FromStr;
+use cosmwasm_std::{Addr, Event};
+use ownable_std::ExternalEventMsg;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use crate::error::ContractError;
+
+// version of the consumable protocol implemented by the ownables
+pub const PROTOCOL_VERSION: u8 = 1;
+
+// seconds after which an unacknowledged consume is rolled back
+pub const CONSUME_TIMEOUT: u64 = 3600;
+
+/// Event passed from one ownable to another through `register_external_event`
+pub trait ExternalEvent: Sized {
+    const EVENT_TYPE: &'static str;
+
+    fn to_event(&self) -> Event;
+
+    fn from_external_event(event: &ExternalEventMsg) -> Result<Self, ContractError>;
+}
+
+/// Emitted by a consumable when it's consumed
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ConsumeEvent {
+    pub version: u8,
+    // None for consumables instantiated before their id was stored
+    pub ownable_id: Option<String>,
+    pub issuer: Addr,
+    pub owner: Addr,
+    pub consumed_by: Addr,
+    pub consumable_type: String,
+    pub color: Option<String>,
+    // how the color is applied to the consumer, None if it replaces the color
+    pub blend: Option<BlendMode>,
+    // block time in seconds at which the consumable rolls back, None if it never does
+    pub expires: Option<u64>,
+}
+
+impl ConsumeEvent {
+    pub fn is_expired(&self, now: u64) -> bool {
+        self.expires.is_some_and(|expires| now >= expires)
+    }
+}
+
+impl ExternalEvent for ConsumeEvent {
+    const EVENT_TYPE: &'static str = "consume";
+
+    fn to_event(&self) -> Event {
+        let mut event = Event::new(Self::EVENT_TYPE)
+            .add_attribute("version", self.version.to_string())
+            .add_attribute("issuer", self.issuer.to_string())
+            .add_attribute("owner", self.owner.to_string())
+            .add_attribute("consumed_by", self.consumed_by.to_string())
+            .add_attribute("consumable_type", self.consumable_type.clone());
+        if let Some(ownable_id) = &self.ownable_id {
+            event = event.add_attribute("ownable_id", ownable_id);
+        }
+        if let Some(color) = &self.color {
+            event = event.add_attribute("color", color);
+        }
+        if let Some(blend) = &self.blend {
+            event = event.add_attribute("blend", blend.to_string());
+        }
+        if let Some(expires) = self.expires {
+            event = event.add_attribute("expires", expires.to_string());
+        }
+        event
+    }
+
+    fn from_external_event(event: &ExternalEventMsg) -> Result<Self, ContractError> {
+        let version = parse_version(event, Self::EVENT_TYPE, PROTOCOL_VERSION)?;
+
+        Ok(ConsumeEvent {
+            version,
+            ownable_id: optional_attribute(event, "ownable_id"),
+            issuer: Addr::unchecked(required_attribute(event, "issuer")?),
+            owner: Addr::unchecked(required_attribute(event, "owner")?),
+            consumed_by: Addr::unchecked(required_attribute(event, "consumed_by")?),
+            consumable_type: required_attribute(event, "consumable_type")?,
+            color: optional_attribute(event, "color"),
+            blend: optional_attribute(event, "blend")
+                .map(|blend| blend.parse::<BlendMode>())
+                .transpose()?,
+            expires: optional_attribute(event, "expires")
+                .map(|expires| expires.parse::<u64>().map_err(|_| ContractError::InvalidExternalEventArgs {}))
+                .transpose()?,
+        })
+    }
+}
+
+/// How the color of a paint is combined with the current color of the consumer
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum BlendMode {
+    #[default]
+    Replace,
+    // ratio is the percentage of the paint in the mix
+    Mix { ratio: u8 },
+    // lighten the current color towards white by a percentage
+    Tint { amount: u8 },
+    // darken the current color towards black by a percentage
+    Shade { amount: u8 },
+}
+
+impl BlendMode {
+    pub fn validate(&self) -> Result<(), ContractError> {
+        match self {
+            BlendMode::Replace => Ok(()),
+            BlendMode::Mix { ratio: p } | BlendMode::Tint { amount: p } | BlendMode::Shade { amount: p } => {
+                if *p > 100 {
+                    return Err(ContractError::CustomError {
+                        val: format!("Blend percentage {} exceeds 100", p),
+                    });
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+// serialized as an event attribute, e.g. "replace" or "mix:50"
+impl fmt::Display for BlendMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BlendMode::Replace => write!(f, "replace"),
+            BlendMode::Mix { ratio } => write!(f, "mix:{}", ratio),
+            BlendMode::Tint { amount } => write!(f, "tint:{}", amount),
+            BlendMode::Shade { amount } => write!(f, "shade:{}", amount),
+        }
+    }
+}
+
+impl FromStr for BlendMode {
+    type Err = ContractError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (mode, value) = match s.split_once(':') {
+            None => (s, None),
+            Some((mode, value)) => (mode, Some(value)),
+        };
+        let percentage = || -> Result<u8, ContractError> {
+            value.and_then(|v| v.parse::<u8>().ok())
+                .ok_or(ContractError::InvalidExternalEventArgs {})
+        };
+
+        let blend = match mode {
+            "replace" if value.is_none() => BlendMode::Replace,
+            "mix" => BlendMode::Mix { ratio: percentage()? },
+            "tint" => BlendMode::Tint { amount: percentage()? },
+            "shade" => BlendMode::Shade { amount: percentage()? },
+            _ => return Err(ContractError::InvalidExternalEventArgs {}),
+        };
+        blend.validate().map_err(|_| ContractError::InvalidExternalEventArgs {})?;
+        Ok(blend)
+    }
+}
+
+/// Emitted by a consumer when a consumable is detached, so it can be used again
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RestoreEvent {
+    pub version: u8,
+    pub ownable_id: String,
+    // id of the consumer that releases the consumable
+    pub consumer: Addr,
+    pub issuer: Addr,
+    pub owner: Addr,
+    pub consumable_type: String,
+}
+
+impl ExternalEvent for RestoreEvent {
+    const EVENT_TYPE: &'static str = "restore";
+
+    fn to_event(&self) -> Event {
+        Event::new(Self::EVENT_TYPE)
+            .add_attribute("version", self.version.to_string())
+            .add_attribute("ownable_id", self.ownable_id.clone())
+            .add_attribute("consumer", self.consumer.to_string())
+            .add_attribute("issuer", self.issuer.to_string())
+            .add_attribute("owner", self.owner.to_string())
+            .add_attribute("consumable_type", self.consumable_type.clone())
+    }
+
+    fn from_external_event(event: &ExternalEventMsg) -> Result<Self, ContractError> {
+        let version = parse_version(event, Self::EVENT_TYPE, PROTOCOL_VERSION)?;
+
+        Ok(RestoreEvent {
+            version,
+            ownable_id: required_attribute(event, "ownable_id")?,
+            consumer: Addr::unchecked(required_attribute(event, "consumer")?),
+            issuer: Addr::unchecked(required_attribute(event, "issuer")?),
+            owner: Addr::unchecked(required_attribute(event, "owner")?),
+            consumable_type: required_attribute(event, "consumable_type")?,
+        })
+    }
+}
+
+/// Returned by a consumer that accepted a consume event, finalises the consumption
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ConsumeAckEvent {
+    pub version: u8,
+    pub ownable_id: Option<String>,
+    pub consumer: Addr,
+}
+
+impl ExternalEvent for ConsumeAckEvent {
+    const EVENT_TYPE: &'static str = "consume_ack";
+
+    fn to_event(&self) -> Event {
+        let mut event = Event::new(Self::EVENT_TYPE)
+            .add_attribute("version", self.version.to_string())
+            .add_attribute("consumer", self.consumer.to_string());
+        if let Some(ownable_id) = &self.ownable_id {
+            event = event.add_attribute("ownable_id", ownable_id);
+        }
+        event
+    }
+
+    fn from_external_event(event: &ExternalEventMsg) -> Result<Self, ContractError> {
+        let version = parse_version(event, Self::EVENT_TYPE, PROTOCOL_VERSION)?;
+
+        Ok(ConsumeAckEvent {
+            version,
+            ownable_id: optional_attribute(event, "ownable_id"),
+            consumer: Addr::unchecked(required_attribute(event, "consumer")?),
+        })
+    }
+}
+
+/// Returned by a consumer that refused a consume event, makes the consumable available again
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ConsumeRejectEvent {
+    pub version: u8,
+    pub ownable_id: Option<String>,
+    pub consumer: Addr,
+    pub reason: String,
+}
+
+impl ExternalEvent for ConsumeRejectEvent {
+    const EVENT_TYPE: &'static str = "consume_reject";
+
+    fn to_event(&self) -> Event {
+        let mut event = Event::new(Self::EVENT_TYPE)
+            .add_attribute("version", self.version.to_string())
+            .add_attribute("consumer", self.consumer.to_string())
+            .add_attribute("reason", self.reason.clone());
+        if let Some(ownable_id) = &self.ownable_id {
+            event = event.add_attribute("ownable_id", ownable_id);
+        }
+        event
+    }
+
+    fn from_external_event(event: &ExternalEventMsg) -> Result<Self, ContractError> {
+        let version = parse_version(event, Self::EVENT_TYPE, PROTOCOL_VERSION)?;
+
+        Ok(ConsumeRejectEvent {
+            version,
+            ownable_id: optional_attribute(event, "ownable_id"),
+            consumer: Addr::unchecked(required_attribute(event, "consumer")?),
+            reason: optional_attribute(event, "reason").unwrap_or_default(),
+        })
+    }
+}
+
+// events without a version predate the versioned protocol and are treated as version 1
+pub fn parse_version(event: &ExternalEventMsg, event_type: &str, supported: u8) -> Result<u8, ContractError> {
+    if event.event_type != event_type {
+        return Err(ContractError::MatchEventError { val: event.event_type.clone() });
+    }
+
+    let version = match optional_attribute(event, "version") {
+        None => 1,
+        Some(v) => v.parse::<u8>().map_err(|_| ContractError::InvalidExternalEventArgs {})?,
+    };
+    if version == 0 || version > supported {
+        return Err(ContractError::UnsupportedEventVersion { version });
+    }
+    Ok(version)
+}
+
+pub fn required_attribute(event: &ExternalEventMsg, key: &str) -> Result<String, ContractError> {
+    optional_attribute(event, key).ok_or(ContractError::InvalidExternalEventArgs {})
+}
+
+pub fn optional_attribute(event: &ExternalEventMsg, key: &str) -> Option<String> {
+    event.attributes.get(key)
+        .filter(|value| !value.is_empty())
+        .cloned()
+}
@@ -0,0 +1,607 @@
+use cosmwasm_std::{Addr, Deps, DepsMut, Env, Event, MessageInfo, Response, Uint128};
+use ownable_std::ExternalEventMsg;
+use crate::address::{same_address, validate_address};
+use crate::error::ContractError;
+use crate::identity::{matched_address, sender_matches};
+use crate::metadata::{update_metadata, validate_metadata};
+use crate::msg::MetadataUpdate;
+use crate::nft::AssetId;
+use crate::state::{NFT_ITEM, METADATA, LOCKED, OWNABLE_INFO, NETWORK_ID, METADATA_LOCK, Approval, APPROVALS, OPERATORS, clear_approvals, record_history, TransferOffer, TRANSFER_OFFER, has_pending_offer, TransferPolicy, TRANSFER_POLICY, APPROVED_RECIPIENT, LAST_TRANSFER, Loan, LOAN, active_loan, Burn, BURNED, LOCKED_AT, LOCK_RECOVERY_DELAY, DEFAULT_LOCK_RECOVERY_DELAY, NftStandard, NFT_STANDARD};
+
+pub fn try_register_lock(
+    info: MessageInfo,
+    env: Env,
+    deps: DepsMut,
+    event: ExternalEventMsg,
+) -> Result<Response, ContractError> {
+    let owner = event.attributes.get("owner")
+        .cloned()
+        .unwrap_or_default();
+    if owner.is_empty() {
+        return Err(ContractError::InvalidExternalEventArgs {});
+    }
+
+    let event_network = event.network.clone().unwrap_or_default();
+    if event_network.is_empty() {
+        return Err(ContractError::MatchChainIdError { val: "No network".to_string() })
+    }
+
+    let nft = NFT_ITEM.load(deps.storage)?;
+    let standard = NFT_STANDARD.may_load(deps.storage)?
+        .unwrap_or_else(|| NftStandard::for_network(&nft.network));
+    let expected = AssetId::from_nft(&nft, &standard)?;
+
+    // the locked NFT is given as CAIP-19 asset id, or as token id and contract
+    let locked = match event.attributes.get("asset_id") {
+        Some(asset_id) => AssetId::parse(asset_id)?,
+        None => {
+            let nft_id = event.attributes.get("token_id")
+                .cloned()
+                .unwrap_or_default();
+            let contract_addr = event.attributes.get("contract")
+                .cloned()
+                .unwrap_or_default();
+            if nft_id.is_empty() || contract_addr.is_empty() {
+                return Err(ContractError::InvalidExternalEventArgs {});
+            }
+            AssetId::new(&event_network, &standard, &contract_addr, &nft_id)?
+        },
+    };
+    if locked.chain_id() != event_network {
+        return Err(ContractError::LockError {
+            val: "network mismatch".to_string()
+        });
+    }
+    locked.check_matches(&expected)?;
+
+    if let NftStandard::Erc1155 { amount } = standard {
+        let locked_amount = event.attributes.get("amount")
+            .and_then(|amount| amount.parse::<Uint128>().ok())
+            .ok_or(ContractError::InvalidExternalEventArgs {})?;
+        if locked_amount != amount {
+            return Err(ContractError::LockError {
+                val: "amount mismatch".to_string()
+            });
+        }
+    }
+
+    match locked.chain_namespace.as_str() {
+        "eip155" | "cosmos" | "lto" => {
+            // release to the address by which the sender owns the locked NFT
+            let address = matched_address(deps.storage, &info.sender, &owner)?
+                .ok_or(ContractError::Unauthorized {
+                    val: "Only the owner can release an ownable".to_string(),
+                })?;
+            Ok(try_release(info, env, deps, address)?)
+        }
+        _ => Err(ContractError::MatchChainIdError { val: event_network }),
+    }
+}
+
+pub fn try_lock(info: MessageInfo, env: Env, deps: DepsMut) -> Result<Response, ContractError> {
+    // only ownable owner can lock it
+    let ownership = OWNABLE_INFO.load(deps.storage)?;
+    if !sender_matches(deps.storage, &info.sender, &ownership.owner)? {
+        return Err(ContractError::Unauthorized {
+            val: "Unauthorized".into(),
+        });
+    }
+    if has_pending_offer(deps.storage, env.block.time.seconds())? {
+        return Err(ContractError::LockError {
+            val: "Unable to lock an ownable with a pending transfer offer".to_string(),
+        });
+    }
+    if active_loan(deps.storage, env.block.time.seconds())?.is_some() {
+        return Err(ContractError::LockError {
+            val: "Unable to lock a lent ownable".to_string(),
+        });
+    }
+    match TRANSFER_POLICY.may_load(deps.storage)?.unwrap_or_default() {
+        TransferPolicy::Soulbound => return Err(ContractError::TransferPolicyError {
+            val: "Unable to lock a soulbound ownable".to_string(),
+        }),
+        // release doesn't go through the issuer, so locking would bypass the approval
+        TransferPolicy::IssuerApproval => return Err(ContractError::TransferPolicyError {
+            val: "Unable to lock an ownable that requires issuer approval".to_string(),
+        }),
+        TransferPolicy::Cooldown { seconds } => {
+            check_cooldown(deps.as_ref(), seconds, env.block.time.seconds())?;
+        },
+        _ => {},
+    }
+
+    let is_locked = LOCKED.update(
+        deps.storage,
+        |mut is_locked| -> Result<_, ContractError> {
+            if is_locked {
+                return Err(
+                    ContractError::LockError { val: "Already locked".to_string() }
+                );
+            }
+            is_locked = true;
+            Ok(is_locked)
+        }
+    )?;
+
+    LOCKED_AT.save(deps.storage, &env.block.time.seconds())?;
+    record_history(deps.storage, env.block.height, "lock", Some(ownership.owner.clone()), ownership.owner)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "try_lock")
+        .add_attribute("is_locked", is_locked.to_string())
+    )
+}
+
+fn try_release(_info: MessageInfo, env: Env, deps: DepsMut, to: Addr) -> Result<Response, ContractError> {
+    let mut is_locked = LOCKED.load(deps.storage)?;
+    if !is_locked {
+        return Err(ContractError::LockError { val: "Not locked".to_string() });
+    }
+    let to = validate_address(to.as_str(), NETWORK_ID.load(deps.storage)?)?;
+    // the recipient is chosen on the other chain, soulbound ownables and ownables in their cooldown can't be locked
+
+    // transfer ownership and unlock
+    let mut ownership = OWNABLE_INFO.load(deps.storage)?;
+    let previous_owner = ownership.owner.clone();
+    ownership.owner = to;
+    is_locked = false;
+
+    OWNABLE_INFO.save(deps.storage, &ownership)?;
+    clear_approvals(deps.storage)?;
+    TRANSFER_OFFER.remove(deps.storage);
+    APPROVED_RECIPIENT.remove(deps.storage);
+    LOAN.remove(deps.storage);
+    LOCKED_AT.remove(deps.storage);
+    // the cooldown of the new owner starts when the ownable is released
+    LAST_TRANSFER.save(deps.storage, &env.block.time.seconds())?;
+    record_history(deps.storage, env.block.height, "release", Some(previous_owner), ownership.owner.clone())?;
+    LOCKED.save(deps.storage, &is_locked)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "try_release")
+        .add_attribute("is_locked", is_locked.to_string())
+        .add_attribute("owner", ownership.owner.to_string())
+    )
+}
+
+pub fn try_update_metadata(
+    info: MessageInfo,
+    deps: DepsMut,
+    metadata: MetadataUpdate,
+) -> Result<Response, ContractError> {
+    let ownership = OWNABLE_INFO.load(deps.storage)?;
+    if info.sender != ownership.issuer {
+        return Err(ContractError::Unauthorized {
+            val: "Only the issuer can update the metadata".to_string(),
+        });
+    }
+
+    // ownables instantiated before metadata could be locked have no immutable fields
+    let lock = METADATA_LOCK.may_load(deps.storage)?.unwrap_or_default();
+    let current = METADATA.load(deps.storage)?;
+    let (updated, changed) = update_metadata(&current, metadata, &lock)?;
+    if changed.is_empty() {
+        return Ok(Response::new().add_attribute("method", "try_update_metadata"));
+    }
+    validate_metadata(&updated)?;
+    METADATA.save(deps.storage, &updated)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "try_update_metadata")
+        .add_event(
+            Event::new("metadata_updated")
+                .add_attribute("fields", changed.join(","))
+        )
+    )
+}
+
+pub fn try_freeze_metadata(info: MessageInfo, deps: DepsMut) -> Result<Response, ContractError> {
+    let ownership = OWNABLE_INFO.load(deps.storage)?;
+    if info.sender != ownership.issuer {
+        return Err(ContractError::Unauthorized {
+            val: "Only the issuer can freeze the metadata".to_string(),
+        });
+    }
+
+    let mut lock = METADATA_LOCK.may_load(deps.storage)?.unwrap_or_default();
+    if lock.frozen {
+        return Err(ContractError::InvalidMetadata {
+            val: "metadata is frozen".to_string(),
+        });
+    }
+    lock.frozen = true;
+    METADATA_LOCK.save(deps.storage, &lock)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "try_freeze_metadata")
+        .add_attribute("frozen", lock.frozen.to_string())
+    )
+}
+
+pub fn try_approve(
+    info: MessageInfo,
+    env: Env,
+    deps: DepsMut,
+    spender: Addr,
+    expires: Option<u64>,
+) -> Result<Response, ContractError> {
+    let now = env.block.time.seconds();
+    let ownership = OWNABLE_INFO.load(deps.storage)?;
+    let is_operator = OPERATORS.may_load(deps.storage, &info.sender)?
+        .is_some_and(|operator| !operator.is_expired(now));
+    if !sender_matches(deps.storage, &info.sender, &ownership.owner)? && !is_operator {
+        return Err(ContractError::Unauthorized {
+            val: "Unable to approve: sender is not the owner or an operator".to_string(),
+        });
+    }
+
+    let spender = validate_address(spender.as_str(), NETWORK_ID.load(deps.storage)?)?;
+    let approval = new_approval(spender, expires, &ownership.owner, now)?;
+    APPROVALS.save(deps.storage, &approval.spender, &approval)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "try_approve")
+        .add_attribute("spender", approval.spender.to_string())
+    )
+}
+
+pub fn try_revoke(
+    info: MessageInfo,
+    env: Env,
+    deps: DepsMut,
+    spender: Addr,
+) -> Result<Response, ContractError> {
+    let now = env.block.time.seconds();
+    let ownership = OWNABLE_INFO.load(deps.storage)?;
+    let is_operator = OPERATORS.may_load(deps.storage, &info.sender)?
+        .is_some_and(|operator| !operator.is_expired(now));
+    if !sender_matches(deps.storage, &info.sender, &ownership.owner)? && !is_operator {
+        return Err(ContractError::Unauthorized {
+            val: "Unable to revoke: sender is not the owner or an operator".to_string(),
+        });
+    }
+
+    APPROVALS.remove(deps.storage, &spender);
+
+    Ok(Response::new()
+        .add_attribute("method", "try_revoke")
+        .add_attribute("spender", spender.to_string())
+    )
+}
+
+pub fn try_approve_all(
+    info: MessageInfo,
+    env: Env,
+    deps: DepsMut,
+    operator: Addr,
+    expires: Option<u64>,
+) -> Result<Response, ContractError> {
+    let ownership = OWNABLE_INFO.load(deps.storage)?;
+    if !sender_matches(deps.storage, &info.sender, &ownership.owner)? {
+        return Err(ContractError::Unauthorized {
+            val: "Only the owner can approve an operator".to_string(),
+        });
+    }
+
+    let operator = validate_address(operator.as_str(), NETWORK_ID.load(deps.storage)?)?;
+    let approval = new_approval(operator, expires, &ownership.owner, env.block.time.seconds())?;
+    OPERATORS.save(deps.storage, &approval.spender, &approval)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "try_approve_all")
+        .add_attribute("operator", approval.spender.to_string())
+    )
+}
+
+pub fn try_revoke_all(
+    info: MessageInfo,
+    deps: DepsMut,
+    operator: Addr,
+) -> Result<Response, ContractError> {
+    let ownership = OWNABLE_INFO.load(deps.storage)?;
+    if !sender_matches(deps.storage, &info.sender, &ownership.owner)? {
+        return Err(ContractError::Unauthorized {
+            val: "Only the owner can revoke an operator".to_string(),
+        });
+    }
+
+    OPERATORS.remove(deps.storage, &operator);
+
+    Ok(Response::new()
+        .add_attribute("method", "try_revoke_all")
+        .add_attribute("operator", operator.to_string())
+    )
+}
+
+fn new_approval(spender: Addr, expires: Option<u64>, owner: &Addr, now: u64) -> Result<Approval, ContractError> {
+    if spender == *owner {
+        return Err(ContractError::CustomError {
+            val: "Unable to approve the owner".to_string(),
+        });
+    }
+    let approval = Approval { spender, expires };
+    if approval.is_expired(now) {
+        return Err(ContractError::CustomError {
+            val: "Unable to approve: expiration is in the past".to_string(),
+        });
+    }
+    Ok(approval)
+}
+
+pub fn try_offer_transfer(
+    info: MessageInfo,
+    env: Env,
+    deps: DepsMut,
+    to: Addr,
+    expires: Option<u64>,
+) -> Result<Response, ContractError> {
+    let ownership = OWNABLE_INFO.load(deps.storage)?;
+    if !sender_matches(deps.storage, &info.sender, &ownership.owner)? {
+        return Err(ContractError::Unauthorized {
+            val: "Only the owner can offer a transfer".to_string(),
+        });
+    }
+    if LOCKED.load(deps.storage)? {
+        return Err(ContractError::LockError {
+            val: "Unable to transfer a locked ownable".to_string(),
+        });
+    }
+    let to = validate_address(to.as_str(), NETWORK_ID.load(deps.storage)?)?;
+    if same_address(&to, &ownership.owner) {
+        return Err(ContractError::CustomError {
+            val: "Unable to transfer: Recipient address is current owner".to_string(),
+        });
+    }
+
+    let offer = TransferOffer { to, expires };
+    if offer.is_expired(env.block.time.seconds()) {
+        return Err(ContractError::CustomError {
+            val: "Unable to offer a transfer: expiration is in the past".to_string(),
+        });
+    }
+    TRANSFER_OFFER.save(deps.storage, &offer)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "try_offer_transfer")
+        .add_attribute("to", offer.to.to_string())
+    )
+}
+
+/// Accept the pending offer, `transfer` is the transfer handler of the ownable
+pub fn try_accept_transfer(
+    info: MessageInfo,
+    env: Env,
+    deps: DepsMut,
+    transfer: fn(MessageInfo, Env, DepsMut, Addr) -> Result<Response, ContractError>,
+) -> Result<Response, ContractError> {
+    let offer = match TRANSFER_OFFER.may_load(deps.storage)? {
+        Some(offer) if !offer.is_expired(env.block.time.seconds()) => offer,
+        _ => return Err(ContractError::CustomError {
+            val: "No pending transfer offer".to_string(),
+        }),
+    };
+    if !sender_matches(deps.storage, &info.sender, &offer.to)? {
+        return Err(ContractError::Unauthorized {
+            val: "Only the recipient can accept the transfer offer".to_string(),
+        });
+    }
+
+    // transfer on behalf of the owner, so the same checks apply as for a direct transfer
+    let ownership = OWNABLE_INFO.load(deps.storage)?;
+    let owner_info = MessageInfo { sender: ownership.owner, funds: vec![] };
+    let response = transfer(owner_info, env, deps, offer.to)?;
+
+    Ok(response.add_attribute("accepted_by", info.sender.to_string()))
+}
+
+pub fn try_cancel_offer(info: MessageInfo, deps: DepsMut) -> Result<Response, ContractError> {
+    let ownership = OWNABLE_INFO.load(deps.storage)?;
+    if !sender_matches(deps.storage, &info.sender, &ownership.owner)? {
+        return Err(ContractError::Unauthorized {
+            val: "Only the owner can cancel a transfer offer".to_string(),
+        });
+    }
+    let offer = TRANSFER_OFFER.may_load(deps.storage)?.ok_or(ContractError::CustomError {
+        val: "No pending transfer offer".to_string(),
+    })?;
+    TRANSFER_OFFER.remove(deps.storage);
+
+    Ok(Response::new()
+        .add_attribute("method", "try_cancel_offer")
+        .add_attribute("to", offer.to.to_string())
+    )
+}
+
+pub fn try_approve_transfer(
+    info: MessageInfo,
+    deps: DepsMut,
+    to: Addr,
+) -> Result<Response, ContractError> {
+    let ownership = OWNABLE_INFO.load(deps.storage)?;
+    if info.sender != ownership.issuer {
+        return Err(ContractError::Unauthorized {
+            val: "Only the issuer can approve a transfer".to_string(),
+        });
+    }
+    let to = validate_address(to.as_str(), NETWORK_ID.load(deps.storage)?)?;
+    if TRANSFER_POLICY.may_load(deps.storage)?.unwrap_or_default() != TransferPolicy::IssuerApproval {
+        return Err(ContractError::TransferPolicyError {
+            val: "Transfers don't require approval of the issuer".to_string(),
+        });
+    }
+    APPROVED_RECIPIENT.save(deps.storage, &to)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "try_approve_transfer")
+        .add_attribute("to", to.to_string())
+    )
+}
+
+pub fn check_transfer_policy(deps: Deps, to: &Addr, now: u64) -> Result<(), ContractError> {
+    match TRANSFER_POLICY.may_load(deps.storage)?.unwrap_or_default() {
+        TransferPolicy::Free => Ok(()),
+        TransferPolicy::Soulbound => Err(ContractError::TransferPolicyError {
+            val: "Unable to transfer a soulbound ownable".to_string(),
+        }),
+        TransferPolicy::IssuerApproval => match APPROVED_RECIPIENT.may_load(deps.storage)? {
+            Some(recipient) if same_address(&recipient, to) => Ok(()),
+            _ => Err(ContractError::TransferPolicyError {
+                val: format!("Transfer to {} is not approved by the issuer", to),
+            }),
+        },
+        TransferPolicy::Cooldown { seconds } => check_cooldown(deps, seconds, now),
+    }
+}
+
+fn check_cooldown(deps: Deps, seconds: u64, now: u64) -> Result<(), ContractError> {
+    let until = LAST_TRANSFER.may_load(deps.storage)?.unwrap_or_default().saturating_add(seconds);
+    if now < until {
+        return Err(ContractError::TransferPolicyError {
+            val: format!("Unable to transfer before {}", until),
+        });
+    }
+    Ok(())
+}
+
+pub fn try_lend(
+    info: MessageInfo,
+    env: Env,
+    deps: DepsMut,
+    to: Addr,
+    until: u64,
+) -> Result<Response, ContractError> {
+    let now = env.block.time.seconds();
+    let ownership = OWNABLE_INFO.load(deps.storage)?;
+    if !sender_matches(deps.storage, &info.sender, &ownership.owner)? {
+        return Err(ContractError::Unauthorized {
+            val: "Only the owner can lend an ownable".to_string(),
+        });
+    }
+    if LOCKED.load(deps.storage)? {
+        return Err(ContractError::LockError {
+            val: "Unable to lend a locked ownable".to_string(),
+        });
+    }
+    if active_loan(deps.storage, now)?.is_some() {
+        return Err(ContractError::CustomError {
+            val: "Ownable is already lent out".to_string(),
+        });
+    }
+    if same_address(&to, &ownership.owner) {
+        return Err(ContractError::CustomError {
+            val: "Unable to lend an ownable to its owner".to_string(),
+        });
+    }
+    if until <= now {
+        return Err(ContractError::CustomError {
+            val: "Unable to lend: end of the loan is in the past".to_string(),
+        });
+    }
+    let to = validate_address(to.as_str(), NETWORK_ID.load(deps.storage)?)?;
+
+    let loan = Loan { borrower: to, until };
+    LOAN.save(deps.storage, &loan)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "try_lend")
+        .add_attribute("borrower", loan.borrower.to_string())
+        .add_attribute("until", loan.until.to_string())
+    )
+}
+
+pub fn try_reclaim(info: MessageInfo, env: Env, deps: DepsMut) -> Result<Response, ContractError> {
+    let loan = LOAN.may_load(deps.storage)?.ok_or(ContractError::CustomError {
+        val: "Ownable is not lent out".to_string(),
+    })?;
+    // the borrower can return the ownable before the loan ends
+    let ownership = OWNABLE_INFO.load(deps.storage)?;
+    if !sender_matches(deps.storage, &info.sender, &ownership.owner)? && !sender_matches(deps.storage, &info.sender, &loan.borrower)? {
+        return Err(ContractError::Unauthorized {
+            val: "Only the owner or the borrower can end a loan".to_string(),
+        });
+    }
+    LOAN.remove(deps.storage);
+
+    Ok(Response::new()
+        .add_attribute("method", "try_reclaim")
+        .add_attribute("borrower", loan.borrower.to_string())
+        .add_attribute("expired", (env.block.time.seconds() >= loan.until).to_string())
+    )
+}
+
+pub fn try_burn(info: MessageInfo, env: Env, deps: DepsMut) -> Result<Response, ContractError> {
+    let ownership = OWNABLE_INFO.load(deps.storage)?;
+    if !sender_matches(deps.storage, &info.sender, &ownership.owner)? {
+        return Err(ContractError::Unauthorized {
+            val: "Only the owner can burn an ownable".to_string(),
+        });
+    }
+    if LOCKED.load(deps.storage)? {
+        return Err(ContractError::LockError {
+            val: "Unable to burn a locked ownable".to_string(),
+        });
+    }
+    if active_loan(deps.storage, env.block.time.seconds())?.is_some() {
+        return Err(ContractError::CustomError {
+            val: "Unable to burn a lent ownable".to_string(),
+        });
+    }
+
+    let burn = Burn {
+        burned_by: info.sender,
+        time: env.block.time.seconds(),
+    };
+    BURNED.save(deps.storage, &burn)?;
+    record_history(deps.storage, env.block.height, "burn", Some(ownership.owner.clone()), ownership.owner)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "try_burn")
+        .add_event(
+            Event::new("burn")
+                .add_attribute("burned_by", burn.burned_by.to_string())
+        )
+    )
+}
+
+/// Unlock an ownable for which the bridge never delivered a release event
+pub fn try_recover_lock(info: MessageInfo, env: Env, deps: DepsMut) -> Result<Response, ContractError> {
+    let ownership = OWNABLE_INFO.load(deps.storage)?;
+    if info.sender != ownership.issuer {
+        return Err(ContractError::Unauthorized {
+            val: "Only the issuer can recover a locked ownable".to_string(),
+        });
+    }
+    if !LOCKED.load(deps.storage)? {
+        return Err(ContractError::LockError { val: "Not locked".to_string() });
+    }
+
+    // without the lock time the recovery delay can't be enforced
+    let locked_at = match LOCKED_AT.may_load(deps.storage)? {
+        None => return Err(ContractError::LockError {
+            val: "Ownable was locked before lock recovery was supported".to_string(),
+        }),
+        Some(locked_at) => locked_at,
+    };
+    let delay = LOCK_RECOVERY_DELAY.may_load(deps.storage)?.unwrap_or(DEFAULT_LOCK_RECOVERY_DELAY);
+    let recoverable_at = locked_at.saturating_add(delay);
+    if env.block.time.seconds() < recoverable_at {
+        return Err(ContractError::LockError {
+            val: format!("Ownable can't be recovered before {}", recoverable_at),
+        });
+    }
+
+    LOCKED.save(deps.storage, &false)?;
+    LOCKED_AT.remove(deps.storage);
+    record_history(deps.storage, env.block.height, "recover", Some(ownership.owner.clone()), ownership.owner.clone())?;
+
+    Ok(Response::new()
+        .add_attribute("method", "try_recover_lock")
+        .add_attribute("is_locked", false.to_string())
+        .add_event(
+            Event::new("recovered")
+                .add_attribute("issuer", info.sender.to_string())
+                .add_attribute("owner", ownership.owner.to_string())
+                .add_attribute("locked_at", locked_at.to_string())
+        )
+    )
+}
@@ -0,0 +1,115 @@
+use cosmwasm_std::{Addr, StdResult, Storage};
+use secp256k1::PublicKey;
+use sha3::{Digest as _, Keccak256};
+use crate::address::{eip55_checksum, same_address, secure_hash, LTO_ADDRESS_VERSION, LTO_CHECKSUM_LENGTH};
+use crate::state::NETWORK_ID;
+
+const ED25519_KEY_LENGTH: usize = 32;
+const SECP256K1_KEY_LENGTH: usize = 33;
+const PUBLIC_KEY_HASH_LENGTH: usize = 20;
+
+/// LTO address of an ed25519 or secp256k1 public key
+pub fn lto_address(public_key: &[u8], network_id: u8) -> String {
+    let mut address = vec![LTO_ADDRESS_VERSION, network_id];
+    address.extend_from_slice(&secure_hash(public_key)[..PUBLIC_KEY_HASH_LENGTH]);
+    let checksum = secure_hash(&address)[..LTO_CHECKSUM_LENGTH].to_vec();
+    address.extend_from_slice(&checksum);
+    bs58::encode(address).into_string()
+}
+
+/// Ethereum address of a secp256k1 public key, None for any other key
+pub fn eth_address(public_key: &[u8]) -> Option<String> {
+    let key = PublicKey::from_slice(public_key).ok()?;
+    let hash = Keccak256::digest(&key.serialize_uncompressed()[1..]);
+    Some(eip55_checksum(&hex::encode(&hash[12..])))
+}
+
+// the sender is either an address or a base58 encoded public key
+fn sender_public_key(sender: &Addr) -> Option<Vec<u8>> {
+    match bs58::decode(sender.as_str()).into_vec() {
+        Ok(key) if key.len() == ED25519_KEY_LENGTH || key.len() == SECP256K1_KEY_LENGTH => Some(key),
+        _ => None,
+    }
+}
+
+/// All addresses the sender is known by, including those derived from its public key
+pub fn sender_addresses(sender: &Addr, network_id: u8) -> Vec<String> {
+    let mut addresses = vec![sender.to_string()];
+    if let Some(public_key) = sender_public_key(sender) {
+        addresses.push(lto_address(&public_key, network_id));
+        addresses.extend(eth_address(&public_key));
+    }
+    addresses
+}
+
+/// The address of the sender that matches the given address, None if the sender isn't known by it
+pub fn matched_address(storage: &dyn Storage, sender: &Addr, address: impl AsRef<str>) -> StdResult<Option<Addr>> {
+    let network_id = NETWORK_ID.load(storage)?;
+    Ok(sender_addresses(sender, network_id)
+        .into_iter()
+        .find(|known| same_address(known, address.as_ref()))
+        .map(Addr::unchecked))
+}
+
+/// Check if the sender is the given address, either directly or through its public key
+pub fn sender_matches(storage: &dyn Storage, sender: &Addr, address: impl AsRef<str>) -> StdResult<bool> {
+    Ok(matched_address(storage, sender, address)?.is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::testing::MockStorage;
+    use super::*;
+
+    // compressed secp256k1 public key of the private key 1
+    const PUBLIC_KEY: &str = "jesTu2BpszP8DKSoi1R5G6ggjHrsrVnboLdx6V47vkoR";
+    const LTO_ADDRESS: &str = "3MskqE3nhit189H4Cp9xNsEVwHQWoBDKXud";
+    const ETH_ADDRESS: &str = "0x7E5F4552091A69125d5DfCb7b8C2659029395Bdf";
+    const OTHER_KEY: &str = "v3KjemAaDRYztCiwdT9X72waHdpTq6tHBxyqqCBfFCf7";
+    const OTHER_ADDRESS: &str = "3NBd71MErsjwmStnj8PQECHP1JL2jvuY2HW";
+
+    fn key(encoded: &str) -> Vec<u8> {
+        bs58::decode(encoded).into_vec().unwrap()
+    }
+
+    fn storage() -> MockStorage {
+        let mut storage = MockStorage::new();
+        NETWORK_ID.save(&mut storage, &b'T').unwrap();
+        storage
+    }
+
+    #[test]
+    fn test_derived_addresses() {
+        assert_eq!(lto_address(&key(PUBLIC_KEY), b'T'), LTO_ADDRESS);
+        assert_eq!(lto_address(&key(OTHER_KEY), b'T'), OTHER_ADDRESS);
+        assert_eq!(eth_address(&key(PUBLIC_KEY)).unwrap(), ETH_ADDRESS);
+        // an ed25519 key has no Ethereum address
+        assert_eq!(eth_address(&[1u8; ED25519_KEY_LENGTH]), None);
+    }
+
+    #[test]
+    fn test_sender_addresses() {
+        let sender = Addr::unchecked(PUBLIC_KEY);
+        assert_eq!(sender_addresses(&sender, b'T'), vec![PUBLIC_KEY, LTO_ADDRESS, ETH_ADDRESS]);
+
+        let sender = Addr::unchecked(OTHER_ADDRESS);
+        assert_eq!(sender_addresses(&sender, b'T'), vec![OTHER_ADDRESS]);
+    }
+
+    #[test]
+    fn test_matched_address() {
+        let storage = storage();
+        let sender = Addr::unchecked(PUBLIC_KEY);
+
+        let matched = matched_address(&storage, &sender, ETH_ADDRESS.to_ascii_lowercase()).unwrap();
+        assert_eq!(matched, Some(Addr::unchecked(ETH_ADDRESS)));
+        let matched = matched_address(&storage, &sender, LTO_ADDRESS).unwrap();
+        assert_eq!(matched, Some(Addr::unchecked(LTO_ADDRESS)));
+        assert_eq!(matched_address(&storage, &sender, OTHER_ADDRESS).unwrap(), None);
+
+        // a sender that isn't a public key is only known by itself
+        let sender = Addr::unchecked("cosmos1hsk6jryyqjfhp5dhc55tc9jtckygx0eph6dd02");
+        assert!(sender_matches(&storage, &sender, "cosmos1hsk6jryyqjfhp5dhc55tc9jtckygx0eph6dd02").unwrap());
+        assert!(!sender_matches(&storage, &sender, OTHER_ADDRESS).unwrap());
+    }
+}
@@ -0,0 +1,12 @@
+pub mod address;
+pub mod error;
+pub mod event;
+pub mod execute;
+pub mod identity;
+pub mod metadata;
+pub mod msg;
+pub mod nft;
+pub mod query;
+pub mod state;
+
+pub use crate::error::ContractError;
@@ -0,0 +1,226 @@
+use cosmwasm_std::{to_json_vec, Binary, StdResult};
+use ownable_std::Metadata;
+use crate::error::ContractError;
+use crate::msg::{MetadataResponse, MetadataUpdate};
+use crate::state::MetadataLock;
+
+pub const METADATA_FIELDS: [&str; 8] = [
+    "image", "image_data", "external_url", "description",
+    "name", "background_color", "animation_url", "youtube_url",
+];
+
+const MAX_NAME_LENGTH: usize = 128;
+const MAX_DESCRIPTION_LENGTH: usize = 2048;
+const MAX_URL_LENGTH: usize = 2048;
+const MAX_IMAGE_DATA_LENGTH: usize = 65536;
+
+const URL_SCHEMES: [&str; 4] = ["https://", "http://", "ipfs://", "ar://"];
+
+/// Merge the metadata supplied by the issuer over the defaults of the ownable
+pub fn merge_metadata(defaults: Metadata, metadata: Option<Metadata>) -> Metadata {
+    match metadata {
+        None => defaults,
+        Some(m) => Metadata {
+            image: m.image.or(defaults.image),
+            image_data: m.image_data.or(defaults.image_data),
+            external_url: m.external_url.or(defaults.external_url),
+            description: m.description.or(defaults.description),
+            name: m.name.or(defaults.name),
+            background_color: m.background_color.or(defaults.background_color),
+            animation_url: m.animation_url.or(defaults.animation_url),
+            youtube_url: m.youtube_url.or(defaults.youtube_url),
+        },
+    }
+}
+
+/// Apply an update to the metadata, returning the new metadata and the fields that changed
+pub fn update_metadata(
+    current: &Metadata,
+    update: MetadataUpdate,
+    lock: &MetadataLock,
+) -> Result<(Metadata, Vec<String>), ContractError> {
+    if lock.frozen {
+        return Err(ContractError::InvalidMetadata {
+            val: "metadata is frozen".to_string(),
+        });
+    }
+
+    let keep = |field: Option<Option<String>>, current: &Option<String>| field.unwrap_or_else(|| current.clone());
+    let updated = Metadata {
+        image: keep(update.image, &current.image),
+        image_data: keep(update.image_data, &current.image_data),
+        external_url: keep(update.external_url, &current.external_url),
+        description: keep(update.description, &current.description),
+        name: keep(update.name, &current.name),
+        background_color: keep(update.background_color, &current.background_color),
+        animation_url: keep(update.animation_url, &current.animation_url),
+        youtube_url: keep(update.youtube_url, &current.youtube_url),
+    };
+    let changed: Vec<String> = changed_fields(current, &updated);
+
+    if let Some(field) = changed.iter().find(|f| lock.immutable_fields.contains(f)) {
+        return Err(ContractError::InvalidMetadata {
+            val: format!("{} is immutable", field),
+        });
+    }
+    Ok((updated, changed))
+}
+
+pub fn validate_immutable_fields(fields: &[String]) -> Result<(), ContractError> {
+    match fields.iter().find(|f| !METADATA_FIELDS.contains(&f.as_str())) {
+        Some(field) => Err(ContractError::InvalidMetadata {
+            val: format!("unknown metadata field {}", field),
+        }),
+        None => Ok(()),
+    }
+}
+
+fn changed_fields(a: &Metadata, b: &Metadata) -> Vec<String> {
+    let fields = [
+        ("image", a.image != b.image),
+        ("image_data", a.image_data != b.image_data),
+        ("external_url", a.external_url != b.external_url),
+        ("description", a.description != b.description),
+        ("name", a.name != b.name),
+        ("background_color", a.background_color != b.background_color),
+        ("animation_url", a.animation_url != b.animation_url),
+        ("youtube_url", a.youtube_url != b.youtube_url),
+    ];
+    fields.iter()
+        .filter(|(_, changed)| *changed)
+        .map(|(field, _)| field.to_string())
+        .collect()
+}
+
+/// Encode the metadata as an ERC-721 token URI
+pub fn token_uri(metadata: &MetadataResponse) -> StdResult<String> {
+    let json = to_json_vec(metadata)?;
+    Ok(format!("data:application/json;base64,{}", Binary::from(json).to_base64()))
+}
+
+pub fn validate_metadata(metadata: &Metadata) -> Result<(), ContractError> {
+    validate_length("name", &metadata.name, MAX_NAME_LENGTH)?;
+    validate_length("description", &metadata.description, MAX_DESCRIPTION_LENGTH)?;
+    validate_length("image_data", &metadata.image_data, MAX_IMAGE_DATA_LENGTH)?;
+
+    validate_url("image", &metadata.image, true)?;
+    validate_url("external_url", &metadata.external_url, false)?;
+    validate_url("animation_url", &metadata.animation_url, false)?;
+    validate_url("youtube_url", &metadata.youtube_url, false)?;
+
+    if let Some(color) = &metadata.background_color {
+        // six hex digits without a leading #, as used by OpenSea
+        if color.len() != 6 || !color.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(ContractError::InvalidMetadata {
+                val: "background_color must be 6 hexadecimal digits".to_string(),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+fn validate_length(field: &str, value: &Option<String>, max: usize) -> Result<(), ContractError> {
+    match value {
+        Some(v) if v.is_empty() || v.len() > max => Err(ContractError::InvalidMetadata {
+            val: format!("{} must be between 1 and {} characters", field, max),
+        }),
+        _ => Ok(()),
+    }
+}
+
+fn validate_url(field: &str, value: &Option<String>, allow_data_uri: bool) -> Result<(), ContractError> {
+    let url = match value {
+        None => return Ok(()),
+        Some(url) => url,
+    };
+
+    let has_scheme = URL_SCHEMES.iter().any(|scheme| url.starts_with(scheme))
+        || (allow_data_uri && url.starts_with("data:"));
+    let max = if allow_data_uri { MAX_IMAGE_DATA_LENGTH } else { MAX_URL_LENGTH };
+
+    if !has_scheme || url.len() > max || url.chars().any(|c| c.is_whitespace()) {
+        return Err(ContractError::InvalidMetadata {
+            val: format!("{} is not a valid url", field),
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metadata() -> Metadata {
+        Metadata {
+            image: Some("ipfs://image".to_string()),
+            image_data: None,
+            external_url: Some("https://example.com".to_string()),
+            description: Some("An ownable".to_string()),
+            name: Some("Ownable".to_string()),
+            background_color: Some("ffffff".to_string()),
+            animation_url: None,
+            youtube_url: None,
+        }
+    }
+
+    #[test]
+    fn test_validate_metadata() {
+        assert!(validate_metadata(&metadata()).is_ok());
+
+        let data_uri = Metadata { image: Some("data:image/svg+xml;base64,PHN2Zz4=".to_string()), ..metadata() };
+        assert!(validate_metadata(&data_uri).is_ok());
+
+        let invalid = [
+            Metadata { name: Some(String::new()), ..metadata() },
+            Metadata { description: Some("a".repeat(MAX_DESCRIPTION_LENGTH + 1)), ..metadata() },
+            Metadata { external_url: Some("data:text/plain,hello".to_string()), ..metadata() },
+            Metadata { animation_url: Some("ftp://example.com".to_string()), ..metadata() },
+            Metadata { youtube_url: Some("https://youtube.com/watch?v=a b".to_string()), ..metadata() },
+            Metadata { background_color: Some("#ffffff".to_string()), ..metadata() },
+        ];
+        for metadata in invalid {
+            assert!(matches!(validate_metadata(&metadata), Err(ContractError::InvalidMetadata { .. })));
+        }
+    }
+
+    #[test]
+    fn test_validate_immutable_fields() {
+        assert!(validate_immutable_fields(&["name".to_string(), "image".to_string()]).is_ok());
+        assert!(validate_immutable_fields(&["owner".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_merge_metadata() {
+        let merged = merge_metadata(metadata(), Some(Metadata {
+            name: Some("Renamed".to_string()),
+            ..Default::default()
+        }));
+        assert_eq!(merged.name, Some("Renamed".to_string()));
+        assert_eq!(merged.description, metadata().description);
+    }
+    #[test]
+    fn test_update_metadata() {
+        let update: MetadataUpdate = cosmwasm_std::from_json(r#"{"name": "Renamed", "external_url": null}"#).unwrap();
+        assert_eq!(update.name, Some(Some("Renamed".to_string())));
+        assert_eq!(update.external_url, Some(None));
+        assert_eq!(update.description, None);
+
+        let (updated, changed) = update_metadata(&metadata(), update, &MetadataLock::default()).unwrap();
+        assert_eq!(updated.name, Some("Renamed".to_string()));
+        assert_eq!(updated.external_url, None);
+        assert_eq!(updated.description, metadata().description);
+        assert_eq!(changed, vec!["external_url".to_string(), "name".to_string()]);
+    }
+
+    #[test]
+    fn test_update_metadata_locked() {
+        let clear_name = MetadataUpdate { name: Some(None), ..Default::default() };
+
+        let lock = MetadataLock { frozen: false, immutable_fields: vec!["name".to_string()] };
+        assert!(update_metadata(&metadata(), clear_name.clone(), &lock).is_err());
+
+        let lock = MetadataLock { frozen: true, immutable_fields: vec![] };
+        assert!(update_metadata(&metadata(), clear_name, &lock).is_err());
+    }
+}
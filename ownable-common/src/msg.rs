@@ -0,0 +1,87 @@
+use ownable_std::{Metadata, NFT, InfoResponse};
+use crate::state::{Approval, Burn, HistoryEntry, Loan, NftStandard, TransferOffer, TransferPolicy};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Metadata update of the issuer, a missing field is kept and an explicit null clears it
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct MetadataUpdate {
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "::serde_with::rust::double_option")]
+    #[schemars(with = "Option<String>")]
+    pub image: Option<Option<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "::serde_with::rust::double_option")]
+    #[schemars(with = "Option<String>")]
+    pub image_data: Option<Option<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "::serde_with::rust::double_option")]
+    #[schemars(with = "Option<String>")]
+    pub external_url: Option<Option<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "::serde_with::rust::double_option")]
+    #[schemars(with = "Option<String>")]
+    pub description: Option<Option<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "::serde_with::rust::double_option")]
+    #[schemars(with = "Option<String>")]
+    pub name: Option<Option<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "::serde_with::rust::double_option")]
+    #[schemars(with = "Option<String>")]
+    pub background_color: Option<Option<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "::serde_with::rust::double_option")]
+    #[schemars(with = "Option<String>")]
+    pub animation_url: Option<Option<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "::serde_with::rust::double_option")]
+    #[schemars(with = "Option<String>")]
+    pub youtube_url: Option<Option<String>>,
+}
+
+/// Metadata with traits derived from the state of the ownable
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MetadataResponse {
+    #[serde(flatten)]
+    pub metadata: Metadata,
+    pub attributes: Vec<MetadataAttribute>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MetadataAttribute {
+    pub trait_type: String,
+    pub value: String,
+}
+
+impl MetadataAttribute {
+    pub fn new(trait_type: &str, value: impl ToString) -> Self {
+        MetadataAttribute {
+            trait_type: trait_type.to_string(),
+            value: value.to_string(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TokenUriResponse {
+    pub nft: Option<NFT>,
+    // data:application/json;base64 uri of the metadata document
+    pub token_uri: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ApprovalsResponse {
+    pub approvals: Vec<Approval>,
+    pub operators: Vec<Approval>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct HistoryResponse {
+    pub entries: Vec<HistoryEntry>,
+}
+
+/// Ownable info including the pending transfer offer
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OwnableInfoResponse {
+    #[serde(flatten)]
+    pub info: InfoResponse,
+    pub transfer_offer: Option<TransferOffer>,
+    pub transfer_policy: TransferPolicy,
+    pub loan: Option<Loan>,
+    pub burned: Option<Burn>,
+    pub locked_at: Option<u64>,
+    pub nft_standard: Option<NftStandard>,
+}
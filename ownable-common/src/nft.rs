@@ -0,0 +1,232 @@
+use std::fmt;
+use ownable_std::NFT;
+use crate::address::{is_eth_address, same_address, validate_cosmos_address};
+use crate::error::ContractError;
+use crate::state::NftStandard;
+
+const MAX_COSMOS_CHAIN_ID_LENGTH: usize = 32;
+
+/// CAIP-19 asset id of an NFT, `{chain_namespace}:{chain_reference}/{asset_namespace}:{asset_reference}/{token_id}`
+#[derive(Clone, Debug, PartialEq)]
+pub struct AssetId {
+    pub chain_namespace: String,
+    pub chain_reference: String,
+    pub asset_namespace: String,
+    // address of the NFT contract
+    pub asset_reference: String,
+    pub token_id: String,
+}
+
+impl AssetId {
+    /// Asset id from its parts, use `validate` to check it
+    pub fn new(
+        chain_id: &str,
+        standard: &NftStandard,
+        asset_reference: &str,
+        token_id: &str,
+    ) -> Result<Self, ContractError> {
+        let (chain_namespace, chain_reference) = split_pair(chain_id)
+            .ok_or_else(|| invalid_asset_id(chain_id, "expected a CAIP-2 chain id"))?;
+
+        let asset_id = AssetId {
+            chain_namespace: chain_namespace.to_string(),
+            chain_reference: chain_reference.to_string(),
+            asset_namespace: standard.asset_namespace().to_string(),
+            asset_reference: asset_reference.to_string(),
+            token_id: token_id.to_string(),
+        };
+        Ok(asset_id)
+    }
+
+    /// Asset id of the NFT the ownable is linked to
+    pub fn from_nft(nft: &NFT, standard: &NftStandard) -> Result<Self, ContractError> {
+        Self::new(&nft.network, standard, &nft.address, &nft.id.to_string())
+    }
+
+    pub fn parse(value: &str) -> Result<Self, ContractError> {
+        let parts: Vec<&str> = value.split('/').collect();
+        let [chain_id, asset_type, token_id] = parts[..] else {
+            return Err(invalid_asset_id(value, "expected chain id, asset type and token id"));
+        };
+        let (chain_namespace, chain_reference) = split_pair(chain_id)
+            .ok_or_else(|| invalid_asset_id(value, "expected a CAIP-2 chain id"))?;
+        let (asset_namespace, asset_reference) = split_pair(asset_type)
+            .ok_or_else(|| invalid_asset_id(value, "expected an asset namespace and reference"))?;
+
+        let asset_id = AssetId {
+            chain_namespace: chain_namespace.to_string(),
+            chain_reference: chain_reference.to_string(),
+            asset_namespace: asset_namespace.to_string(),
+            asset_reference: asset_reference.to_string(),
+            token_id: token_id.to_string(),
+        };
+        asset_id.validate()?;
+        Ok(asset_id)
+    }
+
+    pub fn chain_id(&self) -> String {
+        format!("{}:{}", self.chain_namespace, self.chain_reference)
+    }
+
+    /// Check that a locked asset is the NFT the ownable is linked to
+    pub fn check_matches(&self, expected: &AssetId) -> Result<(), ContractError> {
+        let mismatch = if self.chain_id() != expected.chain_id() {
+            Some("network mismatch")
+        } else if self.asset_namespace != expected.asset_namespace {
+            Some("nft standard mismatch")
+        } else if !same_address(&self.asset_reference, &expected.asset_reference) {
+            Some("locking contract mismatch")
+        } else if self.token_id != expected.token_id {
+            Some("nft_id mismatch")
+        } else {
+            None
+        };
+
+        match mismatch {
+            Some(val) => Err(ContractError::LockError { val: val.to_string() }),
+            None => Ok(()),
+        }
+    }
+
+    /// Check the chain id, contract and token id for the namespaces of the asset id
+    pub fn validate(&self) -> Result<(), ContractError> {
+        let reason = match (self.chain_namespace.as_str(), self.asset_namespace.as_str()) {
+            ("eip155", "erc721" | "erc1155") => {
+                if !is_numeric(&self.chain_reference) {
+                    Some("eip155 chain reference must be numeric")
+                } else if !is_eth_address(&self.asset_reference) {
+                    Some("contract must be an Ethereum address")
+                } else if !is_numeric(&self.token_id) {
+                    Some("token id must be numeric")
+                } else {
+                    None
+                }
+            },
+            ("cosmos", "cw721") => {
+                let valid_chain = !self.chain_reference.is_empty()
+                    && self.chain_reference.len() <= MAX_COSMOS_CHAIN_ID_LENGTH
+                    && self.chain_reference.chars().all(|c| c.is_ascii_alphanumeric() || c == '-');
+                if !valid_chain {
+                    Some("invalid cosmos chain id")
+                } else if validate_cosmos_address(&self.asset_reference).is_err() {
+                    Some("contract must be a cosmos address")
+                } else if self.token_id.is_empty() {
+                    Some("missing token id")
+                } else {
+                    None
+                }
+            },
+            ("lto", "nft") => {
+                if !matches!(self.chain_reference.as_str(), "L" | "T") {
+                    Some("lto chain reference must be L or T")
+                } else if bs58::decode(&self.asset_reference).into_vec().is_err() {
+                    Some("contract must be base58 encoded")
+                } else if self.token_id.is_empty() {
+                    Some("missing token id")
+                } else {
+                    None
+                }
+            },
+            _ => Some("unsupported nft standard for this network"),
+        };
+
+        match reason {
+            Some(reason) => Err(invalid_asset_id(&self.to_string(), reason)),
+            None => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for AssetId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}/{}:{}/{}",
+            self.chain_namespace, self.chain_reference, self.asset_namespace, self.asset_reference, self.token_id,
+        )
+    }
+}
+
+fn split_pair(value: &str) -> Option<(&str, &str)> {
+    match value.split_once(':') {
+        Some((namespace, reference)) if !namespace.is_empty() && !reference.is_empty() => Some((namespace, reference)),
+        _ => None,
+    }
+}
+
+fn is_numeric(value: &str) -> bool {
+    !value.is_empty() && value.chars().all(|c| c.is_ascii_digit())
+}
+
+fn invalid_asset_id(value: &str, reason: &str) -> ContractError {
+    ContractError::InvalidAssetId {
+        val: format!("{}: {}", value, reason),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::Uint128;
+    use super::*;
+
+    const ERC721_ASSET: &str = "eip155:1/erc721:0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed/1";
+    const CW721_ASSET: &str = "cosmos:cosmoshub-4/cw721:cosmos1hsk6jryyqjfhp5dhc55tc9jtckygx0eph6dd02/token-1";
+    const LTO_ASSET: &str = "lto:T/nft:3NBd71MErsjwmStnj8PQECHP1JL2jvuY2HW/1";
+
+    #[test]
+    fn test_parse() {
+        let asset_id = AssetId::parse(ERC721_ASSET).unwrap();
+        assert_eq!(asset_id.chain_id(), "eip155:1");
+        assert_eq!(asset_id.asset_namespace, "erc721");
+        assert_eq!(asset_id.asset_reference, "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed");
+        assert_eq!(asset_id.token_id, "1");
+        assert_eq!(asset_id.to_string(), ERC721_ASSET);
+
+        let asset_id = AssetId::parse(CW721_ASSET).unwrap();
+        assert_eq!(asset_id.chain_id(), "cosmos:cosmoshub-4");
+        assert_eq!(asset_id.token_id, "token-1");
+        assert_eq!(AssetId::parse(LTO_ASSET).unwrap().to_string(), LTO_ASSET);
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        let invalid = [
+            "eip155:1/erc721:0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+            "eip155/erc721:0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed/1",
+            "eip155:1/erc721:0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed/one",
+            "eip155:1/erc721:nft-contract-address/1",
+            "eip155:1/cw721:0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed/1",
+            "cosmos:cosmoshub-4/cw721:cosmos1hsk6jryyqjfhp5dhc55tc9jtckygx0eph6dd03/token-1",
+            "cosmos:cosmos_hub/cw721:cosmos1hsk6jryyqjfhp5dhc55tc9jtckygx0eph6dd02/token-1",
+            "lto:X/nft:3NBd71MErsjwmStnj8PQECHP1JL2jvuY2HW/1",
+        ];
+        for value in invalid {
+            assert!(matches!(AssetId::parse(value), Err(ContractError::InvalidAssetId { .. })), "{}", value);
+        }
+    }
+
+    #[test]
+    fn test_new_is_not_validated() {
+        let asset_id = AssetId::new("eip155:1", &NftStandard::Erc721, "nft-contract-address", "1").unwrap();
+        assert!(asset_id.validate().is_err());
+
+        let standard = NftStandard::Erc1155 { amount: Uint128::new(5) };
+        let asset_id = AssetId::new("eip155:1", &standard, "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed", "1").unwrap();
+        assert_eq!(asset_id.asset_namespace, "erc1155");
+        assert!(asset_id.validate().is_ok());
+    }
+
+    #[test]
+    fn test_check_matches() {
+        let expected = AssetId::parse(ERC721_ASSET).unwrap();
+        let locked = AssetId::parse(&ERC721_ASSET.to_ascii_lowercase()).unwrap();
+        assert!(locked.check_matches(&expected).is_ok());
+
+        let other_token = AssetId { token_id: "2".to_string(), ..expected.clone() };
+        assert!(other_token.check_matches(&expected).is_err());
+        let other_chain = AssetId { chain_reference: "137".to_string(), ..expected.clone() };
+        assert!(other_chain.check_matches(&expected).is_err());
+        let other_standard = AssetId { asset_namespace: "erc1155".to_string(), ..expected.clone() };
+        assert!(other_standard.check_matches(&expected).is_err());
+    }
+}
@@ -0,0 +1,57 @@
+use cosmwasm_std::{to_json_binary, Binary, Deps, Env, Order, StdResult};
+use cw_storage_plus::Bound;
+use ownable_std::InfoResponse;
+use crate::msg::{ApprovalsResponse, HistoryResponse, OwnableInfoResponse};
+use crate::state::{NFT_ITEM, LOCKED, OWNABLE_INFO, APPROVALS, OPERATORS, active_approvals, HISTORY, TRANSFER_OFFER, TRANSFER_POLICY, active_loan, BURNED, LOCKED_AT, PROCESSED_EVENTS, NFT_STANDARD};
+
+const DEFAULT_HISTORY_LIMIT: u32 = 10;
+const MAX_HISTORY_LIMIT: u32 = 30;
+
+pub fn query_event_processed(deps: Deps, event_id: String) -> StdResult<Binary> {
+    let is_processed = PROCESSED_EVENTS.has(deps.storage, &event_id);
+    to_json_binary(&is_processed)
+}
+
+pub fn query_lock_state(deps: Deps) -> StdResult<Binary> {
+    let is_locked = LOCKED.load(deps.storage)?;
+    to_json_binary(&is_locked)
+}
+
+pub fn query_ownable_info(deps: Deps, env: Env) -> StdResult<Binary> {
+    let nft = NFT_ITEM.may_load(deps.storage)?;
+    let ownable_info = OWNABLE_INFO.load(deps.storage)?;
+    to_json_binary(&OwnableInfoResponse {
+        info: InfoResponse {
+            owner: ownable_info.owner,
+            issuer: ownable_info.issuer,
+            nft,
+            ownable_type: ownable_info.ownable_type,
+        },
+        transfer_offer: TRANSFER_OFFER.may_load(deps.storage)?,
+        transfer_policy: TRANSFER_POLICY.may_load(deps.storage)?.unwrap_or_default(),
+        loan: active_loan(deps.storage, env.block.time.seconds())?,
+        burned: BURNED.may_load(deps.storage)?,
+        locked_at: LOCKED_AT.may_load(deps.storage)?,
+        nft_standard: NFT_STANDARD.may_load(deps.storage)?,
+    })
+}
+
+pub fn query_approvals(deps: Deps, env: Env) -> StdResult<Binary> {
+    let now = env.block.time.seconds();
+    to_json_binary(&ApprovalsResponse {
+        approvals: active_approvals(deps.storage, &APPROVALS, now)?,
+        operators: active_approvals(deps.storage, &OPERATORS, now)?,
+    })
+}
+
+pub fn query_history(deps: Deps, start_after: Option<u64>, limit: Option<u32>) -> StdResult<Binary> {
+    let limit = limit.unwrap_or(DEFAULT_HISTORY_LIMIT).min(MAX_HISTORY_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+    let entries = HISTORY
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, entry)| entry))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    to_json_binary(&HistoryResponse { entries })
+}
@@ -0,0 +1,219 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use cosmwasm_std::{Addr, Order, StdResult, Storage, Uint128};
+use cw_storage_plus::{Item, Map};
+use ownable_std::{ExternalEventMsg, Metadata, NFT, OwnableInfo};
+
+/// Metadata fields the issuer can no longer change
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct MetadataLock {
+    pub frozen: bool,
+    pub immutable_fields: Vec<String>,
+}
+
+pub const OWNABLE_INFO: Item<OwnableInfo> = Item::new("ownable_info");
+pub const METADATA: Item<Metadata> = Item::new("metadata");
+pub const NFT_ITEM: Item<NFT> = Item::new("nft");
+pub const LOCKED: Item<bool> = Item::new("is_locked");
+pub const PACKAGE_CID: Item<String> = Item::new("package_cid");
+pub const NETWORK_ID: Item<u8> = Item::new("network_id");
+pub const OWNABLE_ID: Item<String> = Item::new("ownable_id");
+pub const METADATA_LOCK: Item<MetadataLock> = Item::new("metadata_lock");
+
+/// Spender or operator that can transfer the ownable on behalf of the owner
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Approval {
+    pub spender: Addr,
+    // block time in seconds at which the approval ends, never if not set
+    pub expires: Option<u64>,
+}
+
+impl Approval {
+    pub fn is_expired(&self, now: u64) -> bool {
+        self.expires.is_some_and(|expires| now >= expires)
+    }
+}
+
+pub const APPROVALS: Map<&Addr, Approval> = Map::new("approvals");
+pub const OPERATORS: Map<&Addr, Approval> = Map::new("operators");
+
+pub fn is_approved(storage: &dyn Storage, spender: &Addr, now: u64) -> StdResult<bool> {
+    let valid = |approval: Option<Approval>| approval.is_some_and(|a| !a.is_expired(now));
+    Ok(valid(APPROVALS.may_load(storage, spender)?) || valid(OPERATORS.may_load(storage, spender)?))
+}
+
+pub fn active_approvals(
+    storage: &dyn Storage,
+    map: &Map<&Addr, Approval>,
+    now: u64,
+) -> StdResult<Vec<Approval>> {
+    map.range(storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, approval)| approval))
+        .filter(|item| item.as_ref().map_or(true, |approval| !approval.is_expired(now)))
+        .collect()
+}
+
+/// Approvals are given by the owner, so they don't carry over to a new owner
+pub fn clear_approvals(storage: &mut dyn Storage) -> StdResult<()> {
+    for map in [APPROVALS, OPERATORS] {
+        let spenders = map
+            .keys(storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?;
+        for spender in spenders {
+            map.remove(storage, &spender);
+        }
+    }
+    Ok(())
+}
+
+/// Entry of the provenance log, for changes of ownership and state
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct HistoryEntry {
+    // position of the entry in the log, starting at 0
+    pub index: u64,
+    // position in the event chain of the event that made the change, unknown for older entries
+    #[serde(default)]
+    pub event_index: Option<u64>,
+    pub method: String,
+    pub previous_owner: Option<Addr>,
+    pub new_owner: Addr,
+}
+
+pub const HISTORY: Map<u64, HistoryEntry> = Map::new("history");
+
+/// Append an entry to the history, the log is never rewritten
+pub fn record_history(
+    storage: &mut dyn Storage,
+    event_index: u64,
+    method: &str,
+    previous_owner: Option<Addr>,
+    new_owner: Addr,
+) -> StdResult<()> {
+    let index = HISTORY
+        .keys(storage, None, None, Order::Descending)
+        .next()
+        .transpose()?
+        .map_or(0, |last| last + 1);
+
+    HISTORY.save(storage, index, &HistoryEntry {
+        index,
+        event_index: Some(event_index),
+        method: method.to_string(),
+        previous_owner,
+        new_owner,
+    })
+}
+
+/// Recipient of a two-step transfer, ownership only changes once the offer is accepted
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TransferOffer {
+    pub to: Addr,
+    // block time in seconds at which the offer ends, never if not set
+    pub expires: Option<u64>,
+}
+
+impl TransferOffer {
+    pub fn is_expired(&self, now: u64) -> bool {
+        self.expires.is_some_and(|expires| now >= expires)
+    }
+}
+
+pub const TRANSFER_OFFER: Item<TransferOffer> = Item::new("transfer_offer");
+
+pub fn has_pending_offer(storage: &dyn Storage, now: u64) -> StdResult<bool> {
+    Ok(TRANSFER_OFFER.may_load(storage)?.is_some_and(|offer| !offer.is_expired(now)))
+}
+
+/// Restrictions on changing the owner, chosen at instantiate
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TransferPolicy {
+    #[default]
+    Free,
+    // the owner can never change
+    Soulbound,
+    // only to a recipient approved by the issuer
+    IssuerApproval,
+    // not until the given number of seconds after the last change of owner
+    Cooldown { seconds: u64 },
+}
+
+pub const TRANSFER_POLICY: Item<TransferPolicy> = Item::new("transfer_policy");
+pub const APPROVED_RECIPIENT: Item<Addr> = Item::new("approved_recipient");
+// block time in seconds of the last change of owner
+pub const LAST_TRANSFER: Item<u64> = Item::new("last_transfer");
+
+/// Ownable lent out, the borrower has the user rights until the loan ends
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Loan {
+    pub borrower: Addr,
+    // block time in seconds at which the rights go back to the owner
+    pub until: u64,
+}
+
+pub const LOAN: Item<Loan> = Item::new("loan");
+
+pub fn active_loan(storage: &dyn Storage, now: u64) -> StdResult<Option<Loan>> {
+    Ok(LOAN.may_load(storage)?.filter(|loan| now < loan.until))
+}
+
+/// Tombstone of a burned ownable, it can't be changed anymore
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Burn {
+    pub burned_by: Addr,
+    // block time in seconds
+    pub time: u64,
+}
+
+pub const BURNED: Item<Burn> = Item::new("burned");
+
+// block time in seconds at which the ownable got locked
+pub const LOCKED_AT: Item<u64> = Item::new("locked_at");
+// seconds after locking before the issuer can recover the ownable
+pub const LOCK_RECOVERY_DELAY: Item<u64> = Item::new("lock_recovery_delay");
+pub const DEFAULT_LOCK_RECOVERY_DELAY: u64 = 30 * 24 * 60 * 60;
+
+// ids of the external events that are applied, with their event type
+pub const PROCESSED_EVENTS: Map<&str, String> = Map::new("processed_events");
+
+/// Unique id of an external event, from the hash of the source transaction and the index of its log.
+/// None if the event doesn't reference its source.
+pub fn external_event_id(event: &ExternalEventMsg) -> Option<String> {
+    let tx_hash = event.attributes.get("tx_hash").filter(|hash| !hash.is_empty())?;
+    let log_index: u64 = event.attributes.get("log_index")?.parse().ok()?;
+    Some(format!("{}:{}", tx_hash, log_index))
+}
+
+/// Token standard of the NFT the ownable is linked to
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum NftStandard {
+    Erc721,
+    // the amount of tokens that has to be locked
+    Erc1155 { amount: Uint128 },
+    Cw721,
+    Lto,
+}
+
+impl NftStandard {
+    /// Default standard for the namespace of a CAIP-2 network
+    pub fn for_network(network: &str) -> Self {
+        match network.split(':').next() {
+            Some("cosmos") => NftStandard::Cw721,
+            Some("lto") => NftStandard::Lto,
+            _ => NftStandard::Erc721,
+        }
+    }
+
+    /// CAIP-19 asset namespace
+    pub fn asset_namespace(&self) -> &str {
+        match self {
+            NftStandard::Erc721 => "erc721",
+            NftStandard::Erc1155 { .. } => "erc1155",
+            NftStandard::Cw721 => "cw721",
+            NftStandard::Lto => "nft",
+        }
+    }
+}
+
+pub const NFT_STANDARD: Item<NftStandard> = Item::new("nft_standard");
@@ -1,11 +1,19 @@
-use crate::error::ContractError;
+use ownable_common::error::ContractError;
+use ownable_common::execute::{try_register_lock, try_lock, try_update_metadata, try_freeze_metadata, try_approve, try_revoke, try_approve_all, try_revoke_all, try_offer_transfer, try_accept_transfer, try_cancel_offer, try_approve_transfer, check_transfer_policy, try_lend, try_reclaim, try_burn, try_recover_lock};
+use ownable_common::query::{query_event_processed, query_lock_state, query_ownable_info, query_approvals, query_history};
+use ownable_common::nft::AssetId;
+use ownable_common::identity::sender_matches;
+use ownable_common::address::{same_address, validate_address};
+use ownable_common::metadata::{merge_metadata, validate_immutable_fields, validate_metadata, token_uri};
 use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
+use ownable_common::msg::{MetadataResponse, TokenUriResponse};
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::{Addr, Deps, DepsMut, Env, MessageInfo, Response, StdResult};
 use cosmwasm_std::{Binary, to_json_binary};
 use cw2::set_contract_version;
-use crate::state::{NFT_ITEM, CONFIG, METADATA, LOCKED, PACKAGE_CID, OWNABLE_INFO, NETWORK_ID};
-use ownable_std::{ExternalEventMsg, InfoResponse, Metadata, OwnableInfo};
+use crate::state::CONFIG;
+use ownable_common::state::{NFT_ITEM, METADATA, LOCKED, PACKAGE_CID, OWNABLE_INFO, NETWORK_ID, MetadataLock, METADATA_LOCK, clear_approvals, is_approved, record_history, TRANSFER_OFFER, TRANSFER_POLICY, APPROVED_RECIPIENT, LAST_TRANSFER, LOAN, active_loan, BURNED, LOCK_RECOVERY_DELAY, DEFAULT_LOCK_RECOVERY_DELAY, PROCESSED_EVENTS, external_event_id, NftStandard, NFT_STANDARD};
+use ownable_std::{ExternalEventMsg, Metadata, OwnableInfo};
 
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:ownable";
@@ -13,7 +21,7 @@ const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 pub fn instantiate(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
@@ -25,7 +33,7 @@ pub fn instantiate(
         ownable_type: Some("basic".to_string()),
     };
 
-    let metadata = Metadata {
+    let metadata = merge_metadata(Metadata {
         image: None,
         image_data: None,
         external_url: None,
@@ -33,17 +41,35 @@ pub fn instantiate(
         name: Some("Basic Ownable".to_string()),
         background_color: None,
         animation_url: None,
-        youtube_url: None
+        youtube_url: None,
+    }, msg.metadata.clone());
+    validate_metadata(&metadata)?;
+    let metadata_lock = MetadataLock {
+        frozen: false,
+        immutable_fields: msg.immutable_metadata.clone().unwrap_or_default(),
     };
+    validate_immutable_fields(&metadata_lock.immutable_fields)?;
 
     NETWORK_ID.save(deps.storage, &msg.network_id)?;
     CONFIG.save(deps.storage, &None)?;
     if let Some(nft) = msg.nft {
+        // links without an explicit standard predate CAIP-19 and aren't validated
+        if let Some(standard) = &msg.nft_standard {
+            AssetId::from_nft(&nft, standard)?.validate()?;
+        }
+        let standard = msg.nft_standard.clone()
+            .unwrap_or_else(|| NftStandard::for_network(&nft.network));
         NFT_ITEM.save(deps.storage, &nft)?;
+        NFT_STANDARD.save(deps.storage, &standard)?;
     }
     METADATA.save(deps.storage, &metadata)?;
+    METADATA_LOCK.save(deps.storage, &metadata_lock)?;
+    TRANSFER_POLICY.save(deps.storage, &msg.transfer_policy.clone().unwrap_or_default())?;
+    LOCK_RECOVERY_DELAY.save(deps.storage, &msg.lock_recovery_delay.unwrap_or(DEFAULT_LOCK_RECOVERY_DELAY))?;
+    LAST_TRANSFER.save(deps.storage, &env.block.time.seconds())?;
     LOCKED.save(deps.storage, &false)?;
     OWNABLE_INFO.save(deps.storage, &ownable_info)?;
+    record_history(deps.storage, env.block.height, "instantiate", None, ownable_info.owner.clone())?;
     PACKAGE_CID.save(deps.storage, &msg.package)?;
 
     Ok(Response::new()
@@ -54,55 +80,52 @@ pub fn instantiate(
 
 pub fn execute(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
+    if BURNED.may_load(deps.storage)?.is_some() {
+        return Err(ContractError::Burned {});
+    }
     match msg {
-        ExecuteMsg::Transfer { to } => try_transfer(info, deps, to),
-        ExecuteMsg::Lock {} => try_lock(info, deps),
-        _ => Err(ContractError::NotImplemented {}),
+        ExecuteMsg::Transfer { to } => try_transfer(info, env, deps, to),
+        ExecuteMsg::Lock {} => try_lock(info, env, deps),
+        ExecuteMsg::RecoverLock {} => try_recover_lock(info, env, deps),
+        ExecuteMsg::Burn {} => try_burn(info, env, deps),
+        ExecuteMsg::Lend { to, until } => try_lend(info, env, deps, to, until),
+        ExecuteMsg::Reclaim {} => try_reclaim(info, env, deps),
+        ExecuteMsg::ApproveTransfer { to } => try_approve_transfer(info, deps, to),
+        ExecuteMsg::OfferTransfer { to, expires } => try_offer_transfer(info, env, deps, to, expires),
+        ExecuteMsg::AcceptTransfer {} => try_accept_transfer(info, env, deps, try_transfer),
+        ExecuteMsg::CancelOffer {} => try_cancel_offer(info, deps),
+        ExecuteMsg::Approve { spender, expires } => try_approve(info, env, deps, spender, expires),
+        ExecuteMsg::Revoke { spender } => try_revoke(info, env, deps, spender),
+        ExecuteMsg::ApproveAll { operator, expires } => try_approve_all(info, env, deps, operator, expires),
+        ExecuteMsg::RevokeAll { operator } => try_revoke_all(info, deps, operator),
+        ExecuteMsg::UpdateMetadata { metadata } => try_update_metadata(info, deps, metadata),
+        ExecuteMsg::FreezeMetadata {} => try_freeze_metadata(info, deps),
     }
 }
 
-pub fn try_lock(info: MessageInfo, deps: DepsMut) -> Result<Response, ContractError> {
-    // only ownable owner can lock it
-    let ownership = OWNABLE_INFO.load(deps.storage)?;
-    if info.sender.to_string() != ownership.owner {
-        return Err(ContractError::Unauthorized {
-            val: "Unauthorized".into(),
+pub fn try_transfer(info: MessageInfo, env: Env, deps: DepsMut, to: Addr) -> Result<Response, ContractError> {
+    if active_loan(deps.storage, env.block.time.seconds())?.is_some() {
+        return Err(ContractError::CustomError {
+            val: "Unable to transfer a lent ownable".to_string(),
         });
     }
-
-    let is_locked = LOCKED.update(
-        deps.storage,
-        |mut is_locked| -> Result<_, ContractError> {
-            if is_locked {
-                return Err(
-                    ContractError::LockError { val: "Already locked".to_string() }
-                );
-            }
-            is_locked = true;
-            Ok(is_locked)
-        }
-    )?;
-
-    Ok(Response::new()
-        .add_attribute("method", "try_lock")
-        .add_attribute("is_locked", is_locked.to_string())
-    )
-}
-
-pub fn try_transfer(info: MessageInfo, deps: DepsMut, to: Addr) -> Result<Response, ContractError> {
-    let address = info.sender.to_string();
+    let to = validate_address(to.as_str(), NETWORK_ID.load(deps.storage)?)?;
+    check_transfer_policy(deps.as_ref(), &to, env.block.time.seconds())?;
+    let previous_owner = OWNABLE_INFO.load(deps.storage)?.owner;
+    let approved = is_approved(deps.storage, &info.sender, env.block.time.seconds())?
+        || sender_matches(deps.storage, &info.sender, &previous_owner)?;
 
     OWNABLE_INFO.update(deps.storage, |mut config| -> Result<_, ContractError> {
-        if address != config.owner {
+        if !approved {
             return Err(ContractError::Unauthorized {
                 val: "Unauthorized transfer attempt".to_string(),
             });
         }
-        if address == to {
+        if same_address(&to, &config.owner) {
             return Err(ContractError::CustomError {
                 val: "Unable to transfer: Recipient address is current owner".to_string(),
             });
@@ -110,6 +133,13 @@ pub fn try_transfer(info: MessageInfo, deps: DepsMut, to: Addr) -> Result<Respon
         config.owner = to.clone();
         Ok(config)
     })?;
+    clear_approvals(deps.storage)?;
+    TRANSFER_OFFER.remove(deps.storage);
+    APPROVED_RECIPIENT.remove(deps.storage);
+    LOAN.remove(deps.storage);
+    LAST_TRANSFER.save(deps.storage, &env.block.time.seconds())?;
+    record_history(deps.storage, env.block.height, "transfer", Some(previous_owner), to.clone())?;
+
     Ok(Response::new()
         .add_attribute("method", "try_transfer")
         .add_attribute("new_owner", to.to_string())
@@ -118,18 +148,30 @@ pub fn try_transfer(info: MessageInfo, deps: DepsMut, to: Addr) -> Result<Respon
 
 pub fn register_external_event(
     info: MessageInfo,
-    deps: DepsMut,
+    env: Env,
+    mut deps: DepsMut,
     event: ExternalEventMsg,
     _ownable_id: String,
 ) -> Result<Response, ContractError> {
+    if BURNED.may_load(deps.storage)?.is_some() {
+        return Err(ContractError::Burned {});
+    }
+    let event_id = external_event_id(&event).ok_or(ContractError::InvalidExternalEventArgs {})?;
+    if PROCESSED_EVENTS.has(deps.storage, &event_id) {
+        return Err(ContractError::DuplicateEvent { val: event_id });
+    }
+    let event_type = event.event_type.clone();
+
     let mut response = Response::new()
-        .add_attribute("method", "register_external_event");
+        .add_attribute("method", "register_external_event")
+        .add_attribute("event_id", event_id.clone());
 
     match event.event_type.as_str() {
         "lock" => {
             try_register_lock(
                 info,
-                deps,
+                env,
+                deps.branch(),
                 event,
             )?;
             response = response.add_attribute("event_type", "lock");
@@ -137,130 +179,502 @@ pub fn register_external_event(
         _ => return Err(ContractError::MatchEventError { val: event.event_type }),
     };
 
+    PROCESSED_EVENTS.save(deps.storage, &event_id, &event_type)?;
     Ok(response)
 }
 
-fn try_release(_info: MessageInfo, deps: DepsMut, to: Addr) -> Result<Response, ContractError> {
-    let mut is_locked = LOCKED.load(deps.storage)?;
-    if !is_locked {
-        return Err(ContractError::LockError { val: "Not locked".to_string() });
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::GetInfo {} => query_ownable_info(deps, env),
+        QueryMsg::GetMetadata {} => query_ownable_metadata(deps),
+        QueryMsg::GetWidgetState {} => query_ownable_widget_state(deps),
+        QueryMsg::IsLocked {} => query_lock_state(deps),
+        QueryMsg::IsEventProcessed { event_id } => query_event_processed(deps, event_id),
+        QueryMsg::GetHistory { start_after, limit } => query_history(deps, start_after, limit),
+        QueryMsg::GetApprovals {} => query_approvals(deps, env),
+        QueryMsg::GetTokenUri {} => query_token_uri(deps),
     }
+}
 
-    // transfer ownership and unlock
-    let mut ownership = OWNABLE_INFO.load(deps.storage)?;
-    ownership.owner = to;
-    is_locked = false;
+fn query_ownable_widget_state(deps: Deps) -> StdResult<Binary> {
+    let widget_config = CONFIG.load(deps.storage)?;
+    to_json_binary(&widget_config)
+}
 
-    OWNABLE_INFO.save(deps.storage, &ownership)?;
-    LOCKED.save(deps.storage, &is_locked)?;
+fn query_ownable_metadata(deps: Deps) -> StdResult<Binary> {
+    to_json_binary(&load_metadata_response(deps)?)
+}
 
-    Ok(Response::new()
-        .add_attribute("method", "try_release")
-        .add_attribute("is_locked", is_locked.to_string())
-        .add_attribute("owner", ownership.owner.to_string())
-    )
+fn query_token_uri(deps: Deps) -> StdResult<Binary> {
+    let metadata = load_metadata_response(deps)?;
+    to_json_binary(&TokenUriResponse {
+        nft: NFT_ITEM.may_load(deps.storage)?,
+        token_uri: token_uri(&metadata)?,
+    })
 }
 
-fn try_register_lock(
-    info: MessageInfo,
-    deps: DepsMut,
-    event: ExternalEventMsg,
-) -> Result<Response, ContractError> {
-    let owner = event.attributes.get("owner")
-        .cloned()
-        .unwrap_or_default();
-    let nft_id = event.attributes.get("token_id")
-        .cloned()
-        .unwrap_or_default();
-    let contract_addr = event.attributes.get("contract")
-        .cloned()
-        .unwrap_or_default();
-
-    if owner.is_empty() || nft_id.is_empty() || contract_addr.is_empty() {
-        return Err(ContractError::InvalidExternalEventArgs {});
-    }
-
-    let nft = NFT_ITEM.load(deps.storage).unwrap();
-    if nft.id.to_string() != nft_id {
-        return Err(ContractError::LockError {
-            val: "nft_id mismatch".to_string()
-        });
-    } else if nft.address != contract_addr {
-        return Err(ContractError::LockError {
-            val: "locking contract mismatch".to_string()
-        });
+fn load_metadata_response(deps: Deps) -> StdResult<MetadataResponse> {
+    let metadata = METADATA.load(deps.storage)?;
+    // the basic ownable has no state to derive traits from
+    Ok(MetadataResponse {
+        metadata,
+        attributes: vec![],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage};
+    use cosmwasm_std::{from_json, OwnedDeps, Timestamp, Uint128};
+    use ownable_std::NFT;
+    use ownable_common::msg::{ApprovalsResponse, HistoryResponse, MetadataUpdate, OwnableInfoResponse};
+    use ownable_common::state::{Burn, TransferPolicy, LOCKED_AT};
+    use super::*;
+
+    const ISSUER: &str = "3NBd71MErsjwmStnj8PQECHP1JL2jvuY2HW";
+    const BOB: &str = "3NAigR72mUpT3pqxvpB1YK6L6Eyn5zyRAz5";
+    const CAROL: &str = "3N91FLBZASEAJVnbcjPb2TeUj8WT5dihGeb";
+    const NFT_CONTRACT: &str = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed";
+
+    fn env_at(seconds: u64) -> Env {
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(seconds);
+        env
     }
 
-    let event_network = event.network.unwrap_or("".to_string());
-    if event_network == "" {
-        return Err(ContractError::MatchChainIdError { val: "No network".to_string() })
-    } else if event_network != nft.network {
-        return Err(ContractError::LockError {
-            val: "network mismatch".to_string()
-        });
+    fn setup(
+        transfer_policy: Option<TransferPolicy>,
+        lock_recovery_delay: Option<u64>,
+    ) -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+        setup_with(transfer_policy, lock_recovery_delay, None)
     }
 
-    let caip_2_fields: Vec<&str> = event_network.split(":").collect();
-    let namespace = caip_2_fields.get(0).unwrap();
+    fn setup_with(
+        transfer_policy: Option<TransferPolicy>,
+        lock_recovery_delay: Option<u64>,
+        nft: Option<NFT>,
+    ) -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+        let mut deps = mock_dependencies();
+        let msg = instantiate_msg(transfer_policy, lock_recovery_delay, nft);
+        instantiate(deps.as_mut(), env_at(1000), mock_info(ISSUER, &[]), msg).unwrap();
+        deps
+    }
 
-    match *namespace {
-        "eip155" => {
-            let address = info.sender.clone();
-            // assert that owner address is the info.sender
-            if address.to_string() != owner {
-                return Err(ContractError::Unauthorized {
-                    val: "Only the owner can release an ownable".to_string(),
-                });
-            }
+    fn instantiate_msg(
+        transfer_policy: Option<TransferPolicy>,
+        lock_recovery_delay: Option<u64>,
+        nft: Option<NFT>,
+    ) -> InstantiateMsg {
+        InstantiateMsg {
+            ownable_id: "basic-1".to_string(),
+            package: "package-cid".to_string(),
+            nft,
+            ownable_type: None,
+            network_id: b'T',
+            metadata: None,
+            immutable_metadata: None,
+            transfer_policy,
+            lock_recovery_delay,
+            nft_standard: None,
+        }
+    }
+
+    fn owner(deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>) -> Addr {
+        OWNABLE_INFO.load(&deps.storage).unwrap().owner
+    }
 
-            Ok(try_release(info, deps, address)?)
+    fn nft() -> NFT {
+        NFT {
+            network: "eip155:1".to_string(),
+            id: Uint128::new(1),
+            address: NFT_CONTRACT.to_string(),
+            lock_service: None,
         }
-        _ => return Err(ContractError::MatchChainIdError { val: event_network }),
     }
-}
 
+    fn lock_event(owner: &str) -> ExternalEventMsg {
+        let attributes = [
+            ("owner", owner),
+            ("token_id", "1"),
+            ("contract", NFT_CONTRACT),
+            ("tx_hash", "0xabc"),
+            ("log_index", "0"),
+        ];
+        ExternalEventMsg {
+            network: Some("eip155:1".to_string()),
+            event_type: "lock".to_string(),
+            attributes: attributes.iter().map(|(key, value)| (key.to_string(), value.to_string())).collect(),
+        }
+    }
 
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
-    match msg {
-        QueryMsg::GetInfo {} => query_ownable_info(deps),
-        QueryMsg::GetMetadata {} => query_ownable_metadata(deps),
-        QueryMsg::GetWidgetState {} => query_ownable_widget_state(deps),
-        QueryMsg::IsLocked {} => query_lock_state(deps),
+    fn transfer(to: &str) -> ExecuteMsg {
+        ExecuteMsg::Transfer { to: Addr::unchecked(to) }
     }
-}
 
-fn query_ownable_widget_state(deps: Deps) -> StdResult<Binary> {
-    let widget_config = CONFIG.load(deps.storage)?;
-    to_json_binary(&widget_config)
-}
+    #[test]
+    fn test_approval_expires() {
+        let mut deps = setup(None, None);
+        let approve = ExecuteMsg::Approve { spender: Addr::unchecked(BOB), expires: Some(2000) };
+        execute(deps.as_mut(), env_at(1000), mock_info(ISSUER, &[]), approve).unwrap();
 
-fn query_lock_state(deps: Deps) -> StdResult<Binary> {
-    let is_locked = LOCKED.load(deps.storage)?;
-    to_json_binary(&is_locked)
-}
+        let err = execute(deps.as_mut(), env_at(2000), mock_info(BOB, &[]), transfer(CAROL)).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized { .. }));
 
-fn query_ownable_info(deps: Deps) -> StdResult<Binary> {
-    let nft = NFT_ITEM.may_load(deps.storage)?;
-    let ownable_info = OWNABLE_INFO.load(deps.storage)?;
-    to_json_binary(&InfoResponse {
-        owner: ownable_info.owner,
-        issuer: ownable_info.issuer,
-        nft,
-        ownable_type: ownable_info.ownable_type,
-    })
-}
+        execute(deps.as_mut(), env_at(1999), mock_info(BOB, &[]), transfer(CAROL)).unwrap();
+        assert_eq!(owner(&deps), CAROL);
+    }
 
-fn query_ownable_metadata(deps: Deps) -> StdResult<Binary> {
-    let cw721 = METADATA.load(deps.storage)?;
-    to_json_binary(&Metadata {
-        image: cw721.image,
-        image_data: cw721.image_data,
-        external_url: cw721.external_url,
-        description: cw721.description,
-        name: cw721.name,
-        background_color: cw721.background_color,
-        animation_url: cw721.animation_url,
-        youtube_url: cw721.youtube_url,
-    })
-}
+    #[test]
+    fn test_offer_expires() {
+        let mut deps = setup(None, None);
+        let offer = ExecuteMsg::OfferTransfer { to: Addr::unchecked(BOB), expires: Some(2000) };
+        execute(deps.as_mut(), env_at(1000), mock_info(ISSUER, &[]), offer).unwrap();
+
+        execute(deps.as_mut(), env_at(2000), mock_info(BOB, &[]), ExecuteMsg::AcceptTransfer {}).unwrap_err();
+        assert_eq!(owner(&deps), ISSUER);
+
+        execute(deps.as_mut(), env_at(1500), mock_info(BOB, &[]), ExecuteMsg::AcceptTransfer {}).unwrap();
+        assert_eq!(owner(&deps), BOB);
+    }
+
+    #[test]
+    fn test_accept_offer_with_matched_address() {
+        let mut deps = setup(None, None);
+        let offer = ExecuteMsg::OfferTransfer { to: Addr::unchecked("0x7e5f4552091a69125d5dfcb7b8c2659029395bdf"), expires: None };
+        execute(deps.as_mut(), env_at(1000), mock_info(ISSUER, &[]), offer).unwrap();
+
+        // the offer is made to an Ethereum address, the recipient signs with its public key
+        execute(deps.as_mut(), env_at(1000), mock_info(BOB, &[]), ExecuteMsg::AcceptTransfer {}).unwrap_err();
+        let info = mock_info("jesTu2BpszP8DKSoi1R5G6ggjHrsrVnboLdx6V47vkoR", &[]);
+        execute(deps.as_mut(), env_at(1000), info, ExecuteMsg::AcceptTransfer {}).unwrap();
+        assert_eq!(owner(&deps), "0x7E5F4552091A69125d5DfCb7b8C2659029395Bdf");
+    }
+
+    #[test]
+    fn test_loan_ends() {
+        let mut deps = setup(None, None);
+        let lend = ExecuteMsg::Lend { to: Addr::unchecked(BOB), until: 2000 };
+        execute(deps.as_mut(), env_at(1000), mock_info(ISSUER, &[]), lend).unwrap();
 
+        execute(deps.as_mut(), env_at(1999), mock_info(ISSUER, &[]), transfer(CAROL)).unwrap_err();
+
+        execute(deps.as_mut(), env_at(2000), mock_info(ISSUER, &[]), transfer(CAROL)).unwrap();
+        assert_eq!(owner(&deps), CAROL);
+    }
+
+    #[test]
+    fn test_lend_refused() {
+        let mut deps = setup(None, None);
+        let lend = |to: &str| ExecuteMsg::Lend { to: Addr::unchecked(to), until: 2000 };
+
+        execute(deps.as_mut(), env_at(1000), mock_info(ISSUER, &[]), lend(ISSUER)).unwrap_err();
+        let past = ExecuteMsg::Lend { to: Addr::unchecked(BOB), until: 1000 };
+        execute(deps.as_mut(), env_at(1000), mock_info(ISSUER, &[]), past).unwrap_err();
+        execute(deps.as_mut(), env_at(1000), mock_info(BOB, &[]), lend(CAROL)).unwrap_err();
+        let err = execute(deps.as_mut(), env_at(1000), mock_info(ISSUER, &[]), lend("bob")).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidAddress { .. }));
+
+        execute(deps.as_mut(), env_at(1000), mock_info(ISSUER, &[]), lend(BOB)).unwrap();
+        execute(deps.as_mut(), env_at(1000), mock_info(ISSUER, &[]), lend(CAROL)).unwrap_err();
+    }
+
+    #[test]
+    fn test_lend_locked_refused() {
+        let mut deps = setup(None, None);
+        execute(deps.as_mut(), env_at(1000), mock_info(ISSUER, &[]), ExecuteMsg::Lock {}).unwrap();
+
+        let lend = ExecuteMsg::Lend { to: Addr::unchecked(BOB), until: 2000 };
+        let err = execute(deps.as_mut(), env_at(1000), mock_info(ISSUER, &[]), lend).unwrap_err();
+        assert!(matches!(err, ContractError::LockError { .. }));
+    }
+
+    #[test]
+    fn test_reclaim() {
+        let mut deps = setup(None, None);
+        let lend = ExecuteMsg::Lend { to: Addr::unchecked(BOB), until: 2000 };
+        execute(deps.as_mut(), env_at(1000), mock_info(ISSUER, &[]), lend).unwrap();
+
+        // the borrower can return the ownable before the loan ends
+        execute(deps.as_mut(), env_at(1500), mock_info(CAROL, &[]), ExecuteMsg::Reclaim {}).unwrap_err();
+        execute(deps.as_mut(), env_at(1500), mock_info(BOB, &[]), ExecuteMsg::Reclaim {}).unwrap();
+
+        execute(deps.as_mut(), env_at(1500), mock_info(ISSUER, &[]), transfer(CAROL)).unwrap();
+        assert_eq!(owner(&deps), CAROL);
+    }
+
+    #[test]
+    fn test_burn() {
+        let mut deps = setup(None, None);
+        execute(deps.as_mut(), env_at(1500), mock_info(BOB, &[]), ExecuteMsg::Burn {}).unwrap_err();
+        execute(deps.as_mut(), env_at(1500), mock_info(ISSUER, &[]), ExecuteMsg::Burn {}).unwrap();
+
+        let res = query(deps.as_ref(), env_at(1500), QueryMsg::GetInfo {}).unwrap();
+        let info: OwnableInfoResponse = from_json(res).unwrap();
+        assert_eq!(info.burned, Some(Burn { burned_by: Addr::unchecked(ISSUER), time: 1500 }));
+
+        let err = execute(deps.as_mut(), env_at(1500), mock_info(ISSUER, &[]), transfer(BOB)).unwrap_err();
+        assert!(matches!(err, ContractError::Burned {}));
+        let err = register_external_event(
+            mock_info(ISSUER, &[]),
+            env_at(1500),
+            deps.as_mut(),
+            lock_event(ISSUER),
+            "basic-1".to_string(),
+        ).unwrap_err();
+        assert!(matches!(err, ContractError::Burned {}));
+    }
+
+    #[test]
+    fn test_burn_refused() {
+        let mut deps = setup(None, None);
+        let lend = ExecuteMsg::Lend { to: Addr::unchecked(BOB), until: 2000 };
+        execute(deps.as_mut(), env_at(1000), mock_info(ISSUER, &[]), lend).unwrap();
+        execute(deps.as_mut(), env_at(1500), mock_info(ISSUER, &[]), ExecuteMsg::Burn {}).unwrap_err();
+
+        execute(deps.as_mut(), env_at(2000), mock_info(ISSUER, &[]), ExecuteMsg::Lock {}).unwrap();
+        let err = execute(deps.as_mut(), env_at(2000), mock_info(ISSUER, &[]), ExecuteMsg::Burn {}).unwrap_err();
+        assert!(matches!(err, ContractError::LockError { .. }));
+        assert!(BURNED.may_load(&deps.storage).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_cooldown() {
+        let mut deps = setup(Some(TransferPolicy::Cooldown { seconds: 100 }), None);
+
+        let err = execute(deps.as_mut(), env_at(1099), mock_info(ISSUER, &[]), transfer(BOB)).unwrap_err();
+        assert!(matches!(err, ContractError::TransferPolicyError { .. }));
+
+        execute(deps.as_mut(), env_at(1100), mock_info(ISSUER, &[]), transfer(BOB)).unwrap();
+
+        // the cooldown of the new owner starts at the transfer
+        execute(deps.as_mut(), env_at(1150), mock_info(BOB, &[]), transfer(CAROL)).unwrap_err();
+        execute(deps.as_mut(), env_at(1200), mock_info(BOB, &[]), transfer(CAROL)).unwrap();
+        assert_eq!(owner(&deps), CAROL);
+    }
+
+    #[test]
+    fn test_lock_recovery_delay() {
+        let mut deps = setup(None, Some(100));
+        execute(deps.as_mut(), env_at(1000), mock_info(ISSUER, &[]), ExecuteMsg::Lock {}).unwrap();
+
+        let err = execute(deps.as_mut(), env_at(1099), mock_info(ISSUER, &[]), ExecuteMsg::RecoverLock {}).unwrap_err();
+        assert!(matches!(err, ContractError::LockError { .. }));
+
+        execute(deps.as_mut(), env_at(1100), mock_info(ISSUER, &[]), ExecuteMsg::RecoverLock {}).unwrap();
+        assert!(!LOCKED.load(&deps.storage).unwrap());
+    }
+
+    #[test]
+    fn test_lock_recovery_by_issuer_only() {
+        let mut deps = setup(None, Some(100));
+        execute(deps.as_mut(), env_at(1000), mock_info(ISSUER, &[]), transfer(BOB)).unwrap();
+        execute(deps.as_mut(), env_at(1000), mock_info(BOB, &[]), ExecuteMsg::Lock {}).unwrap();
+
+        let err = execute(deps.as_mut(), env_at(1100), mock_info(BOB, &[]), ExecuteMsg::RecoverLock {}).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized { .. }));
+        assert!(LOCKED.load(&deps.storage).unwrap());
+
+        let res = execute(deps.as_mut(), env_at(1100), mock_info(ISSUER, &[]), ExecuteMsg::RecoverLock {}).unwrap();
+        let recovered = res.events.iter().find(|event| event.ty == "recovered").unwrap();
+        assert!(recovered.attributes.iter().any(|attr| attr.key == "locked_at" && attr.value == "1000"));
+        // recovery unlocks the ownable for its owner, it doesn't return it to the issuer
+        assert_eq!(owner(&deps), BOB);
+    }
+
+    #[test]
+    fn test_legacy_lock_not_recoverable() {
+        let mut deps = setup(None, Some(100));
+        execute(deps.as_mut(), env_at(1000), mock_info(ISSUER, &[]), ExecuteMsg::Lock {}).unwrap();
+        // locked before the lock time was stored
+        LOCKED_AT.remove(&mut deps.storage);
+
+        let err = execute(deps.as_mut(), env_at(1_000_000), mock_info(ISSUER, &[]), ExecuteMsg::RecoverLock {}).unwrap_err();
+        assert!(matches!(err, ContractError::LockError { .. }));
+        assert!(LOCKED.load(&deps.storage).unwrap());
+    }
+
+    #[test]
+    fn test_update_metadata_without_changes() {
+        let mut deps = setup(None, None);
+        let update = ExecuteMsg::UpdateMetadata {
+            metadata: MetadataUpdate { name: Some(Some("Basic Ownable".to_string())), ..Default::default() },
+        };
+        let res = execute(deps.as_mut(), env_at(1000), mock_info(ISSUER, &[]), update).unwrap();
+        assert!(res.events.is_empty());
+
+        let update = ExecuteMsg::UpdateMetadata {
+            metadata: MetadataUpdate { description: Some(None), ..Default::default() },
+        };
+        let res = execute(deps.as_mut(), env_at(1000), mock_info(ISSUER, &[]), update).unwrap();
+        assert_eq!(res.events[0].ty, "metadata_updated");
+        assert_eq!(METADATA.load(&deps.storage).unwrap().description, None);
+    }
+
+    #[test]
+    fn test_operator_approves_spender() {
+        let mut deps = setup(None, None);
+        let approve_all = ExecuteMsg::ApproveAll { operator: Addr::unchecked(BOB), expires: None };
+        execute(deps.as_mut(), env_at(1000), mock_info(ISSUER, &[]), approve_all).unwrap();
+
+        // an operator can approve spenders on behalf of the owner
+        let approve = ExecuteMsg::Approve { spender: Addr::unchecked(CAROL), expires: None };
+        execute(deps.as_mut(), env_at(1000), mock_info(BOB, &[]), approve).unwrap();
+
+        execute(deps.as_mut(), env_at(1000), mock_info(CAROL, &[]), transfer(CAROL)).unwrap();
+        assert_eq!(owner(&deps), CAROL);
+
+        // approvals of the previous owner are cleared on transfer
+        let approvals: ApprovalsResponse = from_json(query(deps.as_ref(), env_at(1000), QueryMsg::GetApprovals {}).unwrap()).unwrap();
+        assert!(approvals.approvals.is_empty());
+        assert!(approvals.operators.is_empty());
+    }
+
+    #[test]
+    fn test_revoke_and_approve_owner() {
+        let mut deps = setup(None, None);
+        let approve = ExecuteMsg::Approve { spender: Addr::unchecked(ISSUER), expires: None };
+        execute(deps.as_mut(), env_at(1000), mock_info(ISSUER, &[]), approve).unwrap_err();
+
+        let approve = ExecuteMsg::Approve { spender: Addr::unchecked(BOB), expires: None };
+        execute(deps.as_mut(), env_at(1000), mock_info(ISSUER, &[]), approve).unwrap();
+        let revoke = ExecuteMsg::Revoke { spender: Addr::unchecked(BOB) };
+        execute(deps.as_mut(), env_at(1000), mock_info(ISSUER, &[]), revoke).unwrap();
+
+        let err = execute(deps.as_mut(), env_at(1000), mock_info(BOB, &[]), transfer(CAROL)).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized { .. }));
+    }
+
+    #[test]
+    fn test_approve_invalid_address_refused() {
+        let mut deps = setup(None, None);
+        let approve = ExecuteMsg::Approve { spender: Addr::unchecked("not-an-address"), expires: None };
+        execute(deps.as_mut(), env_at(1000), mock_info(ISSUER, &[]), approve).unwrap_err();
+
+        let approve_all = ExecuteMsg::ApproveAll { operator: Addr::unchecked("not-an-address"), expires: None };
+        execute(deps.as_mut(), env_at(1000), mock_info(ISSUER, &[]), approve_all).unwrap_err();
+
+        let approvals: ApprovalsResponse = from_json(query(deps.as_ref(), env_at(1000), QueryMsg::GetApprovals {}).unwrap()).unwrap();
+        assert!(approvals.approvals.is_empty());
+        assert!(approvals.operators.is_empty());
+    }
+
+    #[test]
+    fn test_history_records_event_index() {
+        let mut deps = setup(None, None);
+        let mut env = env_at(1000);
+        env.block.height = 4;
+        execute(deps.as_mut(), env, mock_info(ISSUER, &[]), transfer(BOB)).unwrap();
+
+        let history: HistoryResponse = from_json(query(
+            deps.as_ref(),
+            env_at(1000),
+            QueryMsg::GetHistory { start_after: None, limit: None },
+        ).unwrap()).unwrap();
+        let transfer = &history.entries[1];
+        assert_eq!(transfer.index, 1);
+        assert_eq!(transfer.event_index, Some(4));
+        assert_eq!(transfer.new_owner, BOB);
+    }
+
+    #[test]
+    fn test_release_ignores_transfer_policy() {
+        let mut deps = setup_with(Some(TransferPolicy::Cooldown { seconds: 3600 }), None, Some(nft()));
+        execute(deps.as_mut(), env_at(5000), mock_info(ISSUER, &[]), ExecuteMsg::Lock {}).unwrap();
+
+        // the NFT was sold on the other chain, the new owner releases the ownable within the cooldown
+        register_external_event(mock_info(BOB, &[]), env_at(6000), deps.as_mut(), lock_event(BOB), "basic-1".to_string()).unwrap();
+        assert_eq!(owner(&deps), BOB);
+        assert!(!LOCKED.load(&deps.storage).unwrap());
+    }
+
+    #[test]
+    fn test_release_to_matched_address() {
+        let mut deps = setup_with(None, None, Some(nft()));
+        execute(deps.as_mut(), env_at(1000), mock_info(ISSUER, &[]), ExecuteMsg::Lock {}).unwrap();
+
+        // the sender is a public key, the NFT is owned by its Ethereum address
+        let owner_address = "0x7e5f4552091a69125d5dfcb7b8c2659029395bdf";
+        let info = mock_info("jesTu2BpszP8DKSoi1R5G6ggjHrsrVnboLdx6V47vkoR", &[]);
+        register_external_event(info, env_at(2000), deps.as_mut(), lock_event(owner_address), "basic-1".to_string()).unwrap();
+        assert_eq!(owner(&deps), "0x7E5F4552091A69125d5DfCb7b8C2659029395Bdf");
+    }
+
+    #[test]
+    fn test_legacy_nft_link() {
+        let legacy = NFT { address: "nft-contract-address".to_string(), ..nft() };
+
+        let mut deps = mock_dependencies();
+        let msg = instantiate_msg(None, None, Some(legacy.clone()));
+        instantiate(deps.as_mut(), env_at(1000), mock_info(ISSUER, &[]), msg).unwrap();
+
+        // an explicit standard is validated
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            nft_standard: Some(NftStandard::Erc721),
+            ..instantiate_msg(None, None, Some(legacy))
+        };
+        let err = instantiate(deps.as_mut(), env_at(1000), mock_info(ISSUER, &[]), msg).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidAssetId { .. }));
+    }
+
+    #[test]
+    fn test_erc1155_lock_amount() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            nft_standard: Some(NftStandard::Erc1155 { amount: Uint128::new(5) }),
+            ..instantiate_msg(None, None, Some(nft()))
+        };
+        instantiate(deps.as_mut(), env_at(1000), mock_info(ISSUER, &[]), msg).unwrap();
+        execute(deps.as_mut(), env_at(1000), mock_info(ISSUER, &[]), ExecuteMsg::Lock {}).unwrap();
+
+        let mut event = lock_event(BOB);
+        let err = register_external_event(mock_info(BOB, &[]), env_at(2000), deps.as_mut(), event.clone(), "basic-1".to_string()).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidExternalEventArgs {}));
+
+        // the locked amount has to be exactly the amount the ownable is linked to
+        for amount in ["4", "6"] {
+            event.attributes.insert("amount".to_string(), amount.to_string());
+            let err = register_external_event(mock_info(BOB, &[]), env_at(2000), deps.as_mut(), event.clone(), "basic-1".to_string()).unwrap_err();
+            assert!(matches!(err, ContractError::LockError { .. }));
+        }
+
+        event.attributes.insert("amount".to_string(), "5".to_string());
+        register_external_event(mock_info(BOB, &[]), env_at(2000), deps.as_mut(), event, "basic-1".to_string()).unwrap();
+        assert_eq!(owner(&deps), BOB);
+    }
+
+    #[test]
+    fn test_cw721_lock_by_asset_id() {
+        let cw721 = NFT {
+            network: "cosmos:cosmoshub-4".to_string(),
+            address: "cosmos1hsk6jryyqjfhp5dhc55tc9jtckygx0eph6dd02".to_string(),
+            ..nft()
+        };
+        let mut deps = setup_with(None, None, Some(cw721));
+        execute(deps.as_mut(), env_at(1000), mock_info(ISSUER, &[]), ExecuteMsg::Lock {}).unwrap();
+
+        let owner_address = "cosmos1qypqxpq9qcrsszg2pvxq6rs0zqg3yyc5lzv7xu";
+        let asset_id = "cosmos:cosmoshub-4/cw721:cosmos1hsk6jryyqjfhp5dhc55tc9jtckygx0eph6dd02/1";
+        let mut event = lock_event(owner_address);
+        event.network = Some("cosmos:cosmoshub-4".to_string());
+        event.attributes.insert("asset_id".to_string(), asset_id.to_string());
+
+        register_external_event(mock_info(owner_address, &[]), env_at(2000), deps.as_mut(), event, "basic-1".to_string()).unwrap();
+        assert_eq!(owner(&deps), owner_address);
+    }
+
+    #[test]
+    fn test_soulbound_lock_refused() {
+        let mut deps = setup_with(Some(TransferPolicy::Soulbound), None, Some(nft()));
+        let err = execute(deps.as_mut(), env_at(1000), mock_info(ISSUER, &[]), ExecuteMsg::Lock {}).unwrap_err();
+        assert!(matches!(err, ContractError::TransferPolicyError { .. }));
+    }
+
+    #[test]
+    fn test_issuer_approval_lock_refused() {
+        let mut deps = setup_with(Some(TransferPolicy::IssuerApproval), None, Some(nft()));
+        let err = execute(deps.as_mut(), env_at(1000), mock_info(ISSUER, &[]), ExecuteMsg::Lock {}).unwrap_err();
+        assert!(matches!(err, ContractError::TransferPolicyError { .. }));
+        assert!(!LOCKED.load(&deps.storage).unwrap());
+    }
+}
@@ -4,6 +4,7 @@ use std::fs::create_dir_all;
 use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
 
 use ownable_paint::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
+use ownable_common::msg::{MetadataResponse, TokenUriResponse, ApprovalsResponse, HistoryResponse, OwnableInfoResponse};
 use ownable_paint::state::{Config};
 use ownable_std::{ExternalEventMsg, InfoResponse, Metadata};
 
@@ -19,5 +20,10 @@ fn main() {
     export_schema(&schema_for!(ExternalEventMsg), &out_dir);
     export_schema(&schema_for!(InfoResponse), &out_dir);
     export_schema(&schema_for!(Metadata), &out_dir);
+    export_schema(&schema_for!(MetadataResponse), &out_dir);
+    export_schema(&schema_for!(TokenUriResponse), &out_dir);
+    export_schema(&schema_for!(ApprovalsResponse), &out_dir);
+    export_schema(&schema_for!(HistoryResponse), &out_dir);
+    export_schema(&schema_for!(OwnableInfoResponse), &out_dir);
     export_schema(&schema_for!(Config), &out_dir);
 }
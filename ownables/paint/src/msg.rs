@@ -1,9 +1,12 @@
-use cosmwasm_std::{Addr};
-use ownable_std::NFT;
+use cosmwasm_std::Addr;
+use ownable_std::{Metadata, NFT};
+use ownable_common::event::BlendMode;
+use ownable_common::msg::MetadataUpdate;
+use ownable_common::state::{NftStandard, TransferPolicy};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use ownable_std_macros::{
-    ownables_transfer, ownables_consume, ownables_lock,
+    ownables_transfer, ownables_lock,
     ownables_query_info, ownables_query_locked, ownables_query_metadata,
     ownables_query_widget_state, ownables_instantiate_msg
 };
@@ -11,14 +14,52 @@ use ownable_std_macros::{
 #[ownables_instantiate_msg]
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
-pub struct InstantiateMsg {}
+pub struct InstantiateMsg {
+    // merged over the default metadata of the ownable
+    pub metadata: Option<Metadata>,
+    // hex color of the paint, a random color if not set
+    pub color: Option<String>,
+    // how the paint is applied to the consumer, replaces its color if not set
+    pub blend: Option<BlendMode>,
+    // metadata fields the issuer can't change after instantiate
+    pub immutable_metadata: Option<Vec<String>>,
+    // defaults to freely transferable
+    pub transfer_policy: Option<TransferPolicy>,
+    // seconds after locking before the issuer can recover the ownable, defaults to 30 days
+    pub lock_recovery_delay: Option<u64>,
+    // defaults to the standard of the network of the NFT, ERC-721 for eip155
+    pub nft_standard: Option<NftStandard>,
+}
 
 #[ownables_transfer]
-#[ownables_consume]
 #[ownables_lock]
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
-pub enum ExecuteMsg {}
+pub enum ExecuteMsg {
+    // consume the ownable, consumer is the id of the ownable that absorbs it
+    Consume { consumer: String },
+    // only the issuer can update or freeze the metadata
+    UpdateMetadata { metadata: MetadataUpdate },
+    FreezeMetadata {},
+    // approved spenders and operators can transfer on behalf of the owner
+    Approve { spender: Addr, expires: Option<u64> },
+    Revoke { spender: Addr },
+    ApproveAll { operator: Addr, expires: Option<u64> },
+    RevokeAll { operator: Addr },
+    // two-step transfer, ownership changes when the recipient accepts the offer
+    OfferTransfer { to: Addr, expires: Option<u64> },
+    AcceptTransfer {},
+    CancelOffer {},
+    // only the issuer can approve the recipient if the transfer policy requires it
+    ApproveTransfer { to: Addr },
+    // the borrower gets the user rights until the given block time in seconds
+    Lend { to: Addr, until: u64 },
+    Reclaim {},
+    // permanently destroys the ownable, only a tombstone is left
+    Burn {},
+    // unlocks an ownable stuck in the locked state, issuer only
+    RecoverLock {},
+}
 
 #[ownables_query_info]
 #[ownables_query_locked]
@@ -26,4 +67,13 @@ pub enum ExecuteMsg {}
 #[ownables_query_widget_state]
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
-pub enum QueryMsg {}
+pub enum QueryMsg {
+    // ERC-721 metadata of the ownable for the NFT it's linked to
+    GetTokenUri {},
+    GetApprovals {},
+    // provenance log, oldest entries first
+    GetHistory { start_after: Option<u64>, limit: Option<u32> },
+    // true if the external event with this id is applied
+    IsEventProcessed { event_id: String },
+}
+
@@ -0,0 +1,25 @@
+use crate::state::Config;
+
+const FALLBACK_COLOR: &str = "#000000";
+
+/// Render the paint as a color swatch, faded once it's consumed
+pub fn render_svg(config: &Config) -> String {
+    let color = safe_color(&config.color);
+    let opacity = if config.consumed_by.is_some() { "0.3" } else { "1" };
+
+    let mut svg = String::from(r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 200 200">"#);
+    svg.push_str(&format!(
+        r##"<rect x="20" y="20" width="160" height="160" rx="24" fill="{}" fill-opacity="{}" stroke="#536b7c" stroke-width="5"/>"##,
+        color, opacity,
+    ));
+    svg.push_str("</svg>");
+    svg
+}
+
+// only hex colors are written into the svg, anything else could inject markup
+fn safe_color(color: &str) -> &str {
+    let valid = color.len() == 7
+        && color.starts_with('#')
+        && color[1..].chars().all(|c| c.is_ascii_hexdigit());
+    if valid { color } else { FALLBACK_COLOR }
+}
@@ -5,6 +5,7 @@ use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
 use ownable_std::{ExternalEventMsg, InfoResponse, Metadata};
 
 use ownable_potion::msg::{InstantiateMsg, ExecuteMsg, QueryMsg};
+use ownable_common::msg::{MetadataResponse, TokenUriResponse, ApprovalsResponse, HistoryResponse, OwnableInfoResponse};
 use ownable_potion::state::{Config};
 
 fn main() {
@@ -19,5 +20,10 @@ fn main() {
     export_schema(&schema_for!(ExternalEventMsg), &out_dir);
     export_schema(&schema_for!(InfoResponse), &out_dir);
     export_schema(&schema_for!(Metadata), &out_dir);
+    export_schema(&schema_for!(MetadataResponse), &out_dir);
+    export_schema(&schema_for!(TokenUriResponse), &out_dir);
+    export_schema(&schema_for!(ApprovalsResponse), &out_dir);
+    export_schema(&schema_for!(HistoryResponse), &out_dir);
+    export_schema(&schema_for!(OwnableInfoResponse), &out_dir);
     export_schema(&schema_for!(Config), &out_dir);
 }
@@ -0,0 +1,55 @@
+use ownable_common::error::ContractError;
+
+type Rgb = (u8, u8, u8);
+
+/// Blend two colors weighted by their volumes, using integer math only
+pub fn blend_by_volume(a: &str, volume_a: u8, b: &str, volume_b: u8) -> Result<String, ContractError> {
+    let total = volume_a as u32 + volume_b as u32;
+    if total == 0 {
+        return Ok(b.to_string());
+    }
+
+    let a = parse_hex(a)?;
+    let b = parse_hex(b)?;
+    let channel = |x: u8, y: u8| -> u8 {
+        // round half up
+        ((x as u32 * volume_a as u32 + y as u32 * volume_b as u32 + total / 2) / total) as u8
+    };
+
+    Ok(format_hex((channel(a.0, b.0), channel(a.1, b.1), channel(a.2, b.2))))
+}
+
+fn parse_hex(color: &str) -> Result<Rgb, ContractError> {
+    let invalid = || ContractError::CustomError { val: format!("Invalid color: {}", color) };
+
+    let hex = color.strip_prefix('#').ok_or_else(invalid)?;
+    if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(invalid());
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).map_err(|_| invalid());
+
+    Ok((channel(0)?, channel(2)?, channel(4)?))
+}
+
+fn format_hex((r, g, b): Rgb) -> String {
+    format!("#{:02X}{:02X}{:02X}", r, g, b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blend_by_volume() {
+        assert_eq!(blend_by_volume("#FF0000", 50, "#0000ff", 50).unwrap(), "#800080");
+        // rounded half up
+        assert_eq!(blend_by_volume("#FF0000", 1, "#000000", 2).unwrap(), "#550000");
+        assert_eq!(blend_by_volume("#ff0000", 100, "#0000FF", 0).unwrap(), "#FF0000");
+        assert_eq!(blend_by_volume("#FF0000", 0, "#0000FF", 30).unwrap(), "#0000FF");
+        // an empty potion takes the color of what's poured in
+        assert_eq!(blend_by_volume("#FF0000", 0, "#0000ff", 0).unwrap(), "#0000ff");
+
+        blend_by_volume("red", 50, "#0000FF", 50).unwrap_err();
+        blend_by_volume("#FF0000", 50, "#0000F", 50).unwrap_err();
+    }
+}
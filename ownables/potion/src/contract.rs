@@ -1,11 +1,23 @@
-use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
-use crate::state::{NFT_ITEM, Config, CONFIG, METADATA, LOCKED, PACKAGE_CID, OWNABLE_INFO, NETWORK_ID};
-use cosmwasm_std::{to_json_binary, Binary};
+use crate::msg::{DrinkUnit, ExecuteMsg, InstantiateMsg, QueryMsg};
+use ownable_common::msg::{MetadataResponse, MetadataAttribute, TokenUriResponse};
+use crate::state::{Config, PendingPour, CONFIG, current_user};
+use ownable_common::state::{NFT_ITEM, METADATA, LOCKED, PACKAGE_CID, OWNABLE_INFO, NETWORK_ID, OWNABLE_ID, MetadataLock, METADATA_LOCK, clear_approvals, is_approved, record_history, TRANSFER_OFFER, TRANSFER_POLICY, APPROVED_RECIPIENT, LAST_TRANSFER, LOAN, active_loan, BURNED, LOCK_RECOVERY_DELAY, DEFAULT_LOCK_RECOVERY_DELAY, PROCESSED_EVENTS, external_event_id, NftStandard, NFT_STANDARD};
+use cosmwasm_std::{to_json_binary, Binary, Event};
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::{Addr, Deps, DepsMut, Env, MessageInfo, Response, StdResult};
 use cw2::set_contract_version;
-use ownable_std::{ExternalEventMsg, get_random_color, InfoResponse, Metadata, OwnableInfo};
-use crate::error::ContractError;
+use ownable_std::{ExternalEventMsg, get_random_color, Metadata, OwnableInfo};
+use crate::color::blend_by_volume;
+use ownable_common::error::ContractError;
+use ownable_common::execute::{try_register_lock, try_update_metadata, try_freeze_metadata, try_approve, try_revoke, try_approve_all, try_revoke_all, try_offer_transfer, try_accept_transfer, try_cancel_offer, try_approve_transfer, check_transfer_policy, try_lend, try_reclaim, try_burn, try_recover_lock};
+use ownable_common::query::{query_event_processed, query_lock_state, query_ownable_info, query_approvals, query_history};
+use ownable_common::nft::AssetId;
+use ownable_common::identity::sender_matches;
+use ownable_common::address::{same_address, validate_address};
+use crate::render::render_svg;
+use ownable_common::metadata::{merge_metadata, validate_immutable_fields, validate_metadata, token_uri};
+use ownable_common::event::ExternalEvent;
+use crate::event::{PourAckEvent, PourEvent, PourRejectEvent, POUR_TIMEOUT, PROTOCOL_VERSION};
 
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:ownable-demo";
@@ -13,7 +25,7 @@ const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 pub fn instantiate(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
@@ -29,9 +41,10 @@ pub fn instantiate(
         max_capacity: 100,
         current_amount: 100,
         color: get_random_color(msg.clone().ownable_id),
+        pending: None,
     };
 
-    let meta = Metadata {
+    let meta = merge_metadata(Metadata {
         image: None,
         image_data: None,
         external_url: None,
@@ -40,17 +53,36 @@ pub fn instantiate(
         background_color: None,
         animation_url: None,
         youtube_url: None,
+    }, msg.metadata.clone());
+    validate_metadata(&meta)?;
+    let metadata_lock = MetadataLock {
+        frozen: false,
+        immutable_fields: msg.immutable_metadata.clone().unwrap_or_default(),
     };
+    validate_immutable_fields(&metadata_lock.immutable_fields)?;
 
     NETWORK_ID.save(deps.storage, &msg.network_id)?;
     CONFIG.save(deps.storage, &Some(config.clone()))?;
     if let Some(nft) = msg.nft {
+        // links without an explicit standard predate CAIP-19 and aren't validated
+        if let Some(standard) = &msg.nft_standard {
+            AssetId::from_nft(&nft, standard)?.validate()?;
+        }
+        let standard = msg.nft_standard.clone()
+            .unwrap_or_else(|| NftStandard::for_network(&nft.network));
         NFT_ITEM.save(deps.storage, &nft)?;
+        NFT_STANDARD.save(deps.storage, &standard)?;
     }
     METADATA.save(deps.storage, &meta)?;
+    METADATA_LOCK.save(deps.storage, &metadata_lock)?;
+    TRANSFER_POLICY.save(deps.storage, &msg.transfer_policy.clone().unwrap_or_default())?;
+    LOCK_RECOVERY_DELAY.save(deps.storage, &msg.lock_recovery_delay.unwrap_or(DEFAULT_LOCK_RECOVERY_DELAY))?;
+    LAST_TRANSFER.save(deps.storage, &env.block.time.seconds())?;
     LOCKED.save(deps.storage, &false)?;
     OWNABLE_INFO.save(deps.storage, &ownable_info)?;
+    record_history(deps.storage, env.block.height, "instantiate", None, ownable_info.owner.clone())?;
     PACKAGE_CID.save(deps.storage, &msg.package)?;
+    OWNABLE_ID.save(deps.storage, &msg.ownable_id)?;
 
     Ok(Response::new()
         .add_attribute("method", "instantiate")
@@ -62,151 +94,272 @@ pub fn instantiate(
 
 pub fn execute(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
+    if BURNED.may_load(deps.storage)?.is_some() {
+        return Err(ContractError::Burned {});
+    }
     match msg {
-        ExecuteMsg::Transfer { to } => try_transfer(info, deps, to),
-        ExecuteMsg::Lock {} => try_lock(info, deps),
-        ExecuteMsg::Drink { amount } => try_drink(info, deps, amount),
+        ExecuteMsg::Transfer { to } => try_transfer(info, env, deps, to),
+        ExecuteMsg::Lock {} => try_lock(info, env, deps),
+        ExecuteMsg::RecoverLock {} => try_recover_lock(info, env, deps),
+        ExecuteMsg::Burn {} => try_burn(info, env, deps),
+        ExecuteMsg::Lend { to, until } => try_lend(info, env, deps, to, until),
+        ExecuteMsg::Reclaim {} => try_reclaim(info, env, deps),
+        ExecuteMsg::ApproveTransfer { to } => try_approve_transfer(info, deps, to),
+        ExecuteMsg::OfferTransfer { to, expires } => try_offer_transfer(info, env, deps, to, expires),
+        ExecuteMsg::AcceptTransfer {} => try_accept_transfer(info, env, deps, try_transfer),
+        ExecuteMsg::CancelOffer {} => try_cancel_offer(info, deps),
+        ExecuteMsg::Approve { spender, expires } => try_approve(info, env, deps, spender, expires),
+        ExecuteMsg::Revoke { spender } => try_revoke(info, env, deps, spender),
+        ExecuteMsg::ApproveAll { operator, expires } => try_approve_all(info, env, deps, operator, expires),
+        ExecuteMsg::RevokeAll { operator } => try_revoke_all(info, deps, operator),
+        ExecuteMsg::UpdateMetadata { metadata } => try_update_metadata(info, deps, metadata),
+        ExecuteMsg::FreezeMetadata {} => try_freeze_metadata(info, deps),
+        ExecuteMsg::Drink { amount, unit } => try_drink(info, env, deps, amount, unit),
+        ExecuteMsg::Pour { target, amount } => try_pour(info, env, deps, target, amount),
     }
 }
 
 pub fn register_external_event(
     info: MessageInfo,
-    deps: DepsMut,
+    env: Env,
+    mut deps: DepsMut,
     event: ExternalEventMsg,
-    _ownable_id: String,
+    ownable_id: String,
 ) -> Result<Response, ContractError> {
+    if BURNED.may_load(deps.storage)?.is_some() {
+        return Err(ContractError::Burned {});
+    }
+    let event_id = external_event_id(&event).ok_or(ContractError::InvalidExternalEventArgs {})?;
+    if PROCESSED_EVENTS.has(deps.storage, &event_id) {
+        return Err(ContractError::DuplicateEvent { val: event_id });
+    }
+    let event_type = event.event_type.clone();
+
     let mut response = Response::new()
-        .add_attribute("method", "register_external_event");
+        .add_attribute("method", "register_external_event")
+        .add_attribute("event_id", event_id.clone());
 
     match event.event_type.as_str() {
         "lock" => {
             try_register_lock(
                 info,
-                deps,
+                env,
+                deps.branch(),
                 event,
             )?;
             response = response.add_attribute("event_type", "lock");
         },
+        "pour" => {
+            let pour_response = try_register_pour(
+                info,
+                env,
+                deps.branch(),
+                event,
+                ownable_id,
+            )?;
+            // the acknowledgement is passed back to the source potion
+            response = response
+                .add_attribute("event_type", "pour")
+                .add_events(pour_response.events);
+        },
+        "pour_ack" => {
+            let ack_response = try_register_pour_ack(
+                info,
+                env,
+                deps.branch(),
+                event,
+                ownable_id,
+            )?;
+            response = response
+                .add_attribute("event_type", "pour_ack")
+                .add_events(ack_response.events);
+        },
+        "pour_reject" => {
+            try_register_pour_reject(
+                info,
+                env,
+                deps.branch(),
+                event,
+                ownable_id,
+            )?;
+            response = response.add_attribute("event_type", "pour_reject");
+        },
         _ => return Err(ContractError::MatchEventError { val: event.event_type }),
     };
 
+    PROCESSED_EVENTS.save(deps.storage, &event_id, &event_type)?;
     Ok(response)
 }
 
-fn try_register_lock(
-    info: MessageInfo,
+fn try_register_pour(
+    _info: MessageInfo,
+    env: Env,
     deps: DepsMut,
     event: ExternalEventMsg,
+    ownable_id: String,
 ) -> Result<Response, ContractError> {
-    let owner = event.attributes.get("owner")
-        .cloned()
-        .unwrap_or_default();
-    let nft_id = event.attributes.get("token_id")
-        .cloned()
-        .unwrap_or_default();
-    let contract_addr = event.attributes.get("contract")
-        .cloned()
-        .unwrap_or_default();
-
-    if owner.is_empty() || nft_id.is_empty() || contract_addr.is_empty() {
+    let pour = PourEvent::from_external_event(&event)?;
+    if pour.target != ownable_id || pour.ownable_id.as_ref() == Some(&ownable_id) {
         return Err(ContractError::InvalidExternalEventArgs {});
     }
 
-    let nft = NFT_ITEM.load(deps.storage).unwrap();
-    if nft.id.to_string() != nft_id {
-        return Err(ContractError::LockError {
-            val: "nft_id mismatch".to_string()
+    match apply_pour(env, deps, &pour) {
+        Ok(config) => Ok(Response::new()
+            .add_attribute("method", "try_register_pour")
+            .add_attribute("status", "success")
+            .add_attribute("new_amount", config.current_amount.to_string())
+            .add_attribute("color", config.color)
+            .add_event(PourAckEvent {
+                version: PROTOCOL_VERSION,
+                ownable_id: pour.ownable_id,
+                target: ownable_id,
+            }.to_event())
+        ),
+        Err(ContractError::Std(err)) => Err(ContractError::Std(err)),
+        // a rejected pour stays in the source potion instead of being lost
+        Err(err) => Ok(Response::new()
+            .add_attribute("method", "try_register_pour")
+            .add_attribute("status", "rejected")
+            .add_event(PourRejectEvent {
+                version: PROTOCOL_VERSION,
+                ownable_id: pour.ownable_id,
+                target: ownable_id,
+                reason: err.to_string(),
+            }.to_event())
+        ),
+    }
+}
+
+fn apply_pour(env: Env, deps: DepsMut, pour: &PourEvent) -> Result<Config, ContractError> {
+    // the source rolls back once the pour expires, so it can't be added anymore
+    if pour.is_expired(env.block.time.seconds()) {
+        return Err(ContractError::CustomError {
+            val: "Pour event expired".to_string(),
         });
-    } else if nft.address != contract_addr {
+    }
+
+    let is_locked = LOCKED.load(deps.storage)?;
+    if is_locked {
         return Err(ContractError::LockError {
-            val: "locking contract mismatch".to_string()
+            val: "Unable to pour into a locked potion".to_string(),
         });
     }
+    let ownership = OWNABLE_INFO.load(deps.storage)?;
+    if ownership.issuer != pour.issuer {
+        return Err(ContractError::InvalidExternalEventArgs {});
+    }
 
-    let event_network = event.network.unwrap_or("".to_string());
-    if event_network == "" {
-        return Err(ContractError::MatchChainIdError { val: "No network".to_string() })
-    } else if event_network != nft.network {
-        return Err(ContractError::LockError {
-            val: "network mismatch".to_string()
+    let mut config = match CONFIG.load(deps.storage)? {
+        None => return Err(ContractError::CustomError { val: "No config found".to_string() }),
+        Some(c) => c,
+    };
+
+    // a pour that doesn't fit is refused instead of spilled
+    let space = config.max_capacity.saturating_sub(config.current_amount);
+    if pour.amount > space {
+        return Err(ContractError::CustomError {
+            val: format!("Unable to pour {} into a potion with room for {}", pour.amount, space),
         });
     }
+    config.color = blend_by_volume(&config.color, config.current_amount, &pour.color, pour.amount)?;
+    config.current_amount += pour.amount;
+    CONFIG.save(deps.storage, &Some(config.clone()))?;
 
-    let caip_2_fields: Vec<&str> = event_network.split(":").collect();
-    let namespace = caip_2_fields.get(0).unwrap();
+    Ok(config)
+}
 
-    match *namespace {
-        "eip155" => {
-            let address = info.sender.clone();
-            if address.to_string() != owner {
-                return Err(ContractError::Unauthorized {
-                    val: "Only the owner can release an ownable".to_string(),
-                });
-            }
+fn try_register_pour_ack(
+    _info: MessageInfo,
+    env: Env,
+    deps: DepsMut,
+    event: ExternalEventMsg,
+    ownable_id: String,
+) -> Result<Response, ContractError> {
+    let ack = PourAckEvent::from_external_event(&event)?;
+    let mut config = load_pending_config(deps.as_ref(), &env, ack.ownable_id, &ack.target, ownable_id)?;
 
-            Ok(try_release(info, deps, address)?)
-        }
-        _ => return Err(ContractError::MatchChainIdError { val: event_network }),
+    // the poured amount was reserved, it's only debited now the target accepted it
+    let amount = config.pending.take().map_or(0, |pending| pending.amount);
+    config.current_amount -= amount;
+    CONFIG.save(deps.storage, &Some(config.clone()))?;
+
+    let mut response = Response::new()
+        .add_attribute("method", "try_register_pour_ack")
+        .add_attribute("new_amount", config.current_amount.to_string());
+    if config.is_empty() {
+        let owner = OWNABLE_INFO.load(deps.storage)?.owner;
+        response = response.add_event(
+            Event::new("emptied")
+                .add_attribute("owner", owner.to_string())
+                .add_attribute("color", config.color.clone())
+        );
     }
+    Ok(response)
 }
 
-pub fn try_lock(info: MessageInfo, deps: DepsMut) -> Result<Response, ContractError> {
-    // only ownable owner can lock it
-    let ownership = OWNABLE_INFO.load(deps.storage)?;
-    if info.sender.to_string() != ownership.owner {
-        return Err(ContractError::Unauthorized {
-            val: "Unauthorized".into(),
-        });
-    }
+fn try_register_pour_reject(
+    _info: MessageInfo,
+    env: Env,
+    deps: DepsMut,
+    event: ExternalEventMsg,
+    ownable_id: String,
+) -> Result<Response, ContractError> {
+    let reject = PourRejectEvent::from_external_event(&event)?;
+    let mut config = load_pending_config(deps.as_ref(), &env, reject.ownable_id, &reject.target, ownable_id)?;
 
-    let is_locked = LOCKED.update(
-        deps.storage,
-        |mut is_locked| -> Result<_, ContractError> {
-            if is_locked {
-                return Err(
-                    ContractError::LockError { val: "Already locked".to_string() }
-                );
-            }
-            is_locked = true;
-            Ok(is_locked)
-        }
-    )?;
+    config.pending = None;
+    CONFIG.save(deps.storage, &Some(config))?;
 
     Ok(Response::new()
-        .add_attribute("method", "try_lock")
-        .add_attribute("is_locked", is_locked.to_string())
+        .add_attribute("method", "try_register_pour_reject")
+        .add_attribute("reason", reject.reason)
     )
 }
 
-fn try_release(_info: MessageInfo, deps: DepsMut, to: Addr) -> Result<Response, ContractError> {
-    let mut is_locked = LOCKED.load(deps.storage)?;
-    if !is_locked {
-        return Err(ContractError::LockError { val: "Not locked".to_string() });
+fn load_pending_config(
+    deps: Deps,
+    env: &Env,
+    event_ownable_id: Option<String>,
+    target: &str,
+    ownable_id: String,
+) -> Result<Config, ContractError> {
+    if event_ownable_id.is_some_and(|id| id != ownable_id) {
+        return Err(ContractError::InvalidExternalEventArgs {});
     }
 
-    // transfer ownership and unlock
-    let mut ownership = OWNABLE_INFO.load(deps.storage)?;
-    ownership.owner = to;
-    is_locked = false;
-
-    OWNABLE_INFO.save(deps.storage, &ownership)?;
-    LOCKED.save(deps.storage, &is_locked)?;
+    let config = match CONFIG.load(deps.storage)? {
+        None => return Err(ContractError::CustomError { val: "No config found".to_string() }),
+        Some(c) => c,
+    };
+    // a pour that timed out is rolled back, so a late response is refused
+    match &config.pending {
+        Some(pending) if pending.target == target && config.is_pending(env.block.time.seconds(), POUR_TIMEOUT) => Ok(config),
+        _ => Err(ContractError::CustomError {
+            val: "No pending pour for this target".to_string(),
+        }),
+    }
+}
 
-    Ok(Response::new()
-        .add_attribute("method", "try_release")
-        .add_attribute("is_locked", is_locked.to_string())
-        .add_attribute("owner", ownership.owner.to_string())
-    )
+// the poured amount is reserved until the target responds or the pour times out
+fn check_not_pouring(config: &Config, now: u64) -> Result<(), ContractError> {
+    if config.is_pending(now, POUR_TIMEOUT) {
+        return Err(ContractError::CustomError {
+            val: "Unable to change a potion that is being poured".to_string(),
+        });
+    }
+    Ok(())
 }
 
 pub fn try_drink(
     info: MessageInfo,
+    env: Env,
     deps: DepsMut,
-    consumption_amount: u8,
+    amount: u8,
+    unit: DrinkUnit,
 ) -> Result<Response, ContractError> {
     let is_locked = LOCKED.load(deps.storage)?;
     if is_locked {
@@ -216,7 +369,8 @@ pub fn try_drink(
     }
     let ownership = OWNABLE_INFO.load(deps.storage)?;
     let config = CONFIG.load(deps.storage)?;
-    if info.sender.to_string() != ownership.owner {
+    let user = current_user(deps.storage, &ownership.owner, env.block.time.seconds())?;
+    if !sender_matches(deps.storage, &info.sender, &user)? {
         return Err(ContractError::Unauthorized {
             val: "Unable to drink potion".into(),
         });
@@ -225,40 +379,168 @@ pub fn try_drink(
     match config {
         None => Err(ContractError::CustomError { val: "No config found".to_string() }),
         Some(mut c) => {
-            if c.current_amount < consumption_amount {
-                return Err(ContractError::CustomError {
-                    val: "Attempt to drink more than is available".into(),
-                });
+            if c.is_empty() {
+                return Err(ContractError::PotionEmpty {});
             }
+            check_not_pouring(&c, env.block.time.seconds())?;
+            let consumption_amount = drink_amount(&c, amount, unit)?;
             c.current_amount -= consumption_amount;
             CONFIG.save(deps.storage, &Some(c.clone()))?;
 
-            Ok(Response::new()
+            let mut response = Response::new()
                 .add_attribute("method", "try_drink")
                 .add_attribute(
                     "new_amount",
                     c.current_amount.to_string()
-                )
-            )
+                );
+            if c.is_empty() {
+                response = response.add_event(
+                    Event::new("emptied")
+                        .add_attribute("owner", ownership.owner.to_string())
+                        .add_attribute("color", c.color.clone())
+                );
+            }
+            Ok(response)
+        }
+    }
+}
+
+pub fn try_pour(
+    info: MessageInfo,
+    env: Env,
+    deps: DepsMut,
+    target: String,
+    amount: u8,
+) -> Result<Response, ContractError> {
+    let is_locked = LOCKED.load(deps.storage)?;
+    if is_locked {
+        return Err(ContractError::LockError {
+            val: "Unable to pour a locked potion".to_string(),
+        });
+    }
+    let ownership = OWNABLE_INFO.load(deps.storage)?;
+    if !sender_matches(deps.storage, &info.sender, &ownership.owner)? {
+        return Err(ContractError::Unauthorized {
+            val: "Unable to pour potion".into(),
+        });
+    }
+    if active_loan(deps.storage, env.block.time.seconds())?.is_some() {
+        return Err(ContractError::CustomError {
+            val: "Unable to pour a lent potion".into(),
+        });
+    }
+
+    let mut config = match CONFIG.load(deps.storage)? {
+        None => return Err(ContractError::CustomError { val: "No config found".to_string() }),
+        Some(c) => c,
+    };
+    if config.is_empty() {
+        return Err(ContractError::PotionEmpty {});
+    }
+    check_not_pouring(&config, env.block.time.seconds())?;
+    let amount = drink_amount(&config, amount, DrinkUnit::Absolute)?;
+
+    let ownable_id = OWNABLE_ID.may_load(deps.storage)?;
+    if target.is_empty() || Some(&target) == ownable_id.as_ref() {
+        return Err(ContractError::CustomError {
+            val: "Invalid pour target".into(),
+        });
+    }
+
+    // reserved until the target acknowledges the pour
+    let since = env.block.time.seconds();
+    config.pending = Some(PendingPour { target: target.clone(), amount, since });
+    CONFIG.save(deps.storage, &Some(config.clone()))?;
+
+    let event = PourEvent {
+        version: PROTOCOL_VERSION,
+        ownable_id,
+        target,
+        issuer: ownership.issuer,
+        owner: ownership.owner,
+        amount,
+        color: config.color,
+        expires: Some(since + POUR_TIMEOUT),
+    }.to_event();
+
+    Ok(Response::new()
+        .add_attribute("method", "try_pour")
+        .add_attribute("external_event", true.to_string())
+        .add_event(event)
+    )
+}
+
+// amount to subtract from the current amount, validated against the capacity of the potion
+fn drink_amount(config: &Config, amount: u8, unit: DrinkUnit) -> Result<u8, ContractError> {
+    if amount == 0 {
+        return Err(ContractError::CustomError {
+            val: "Drink amount must be greater than 0".into(),
+        });
+    }
+
+    match unit {
+        DrinkUnit::Absolute => {
+            if amount > config.current_amount {
+                return Err(ContractError::CustomError {
+                    val: "Attempt to drink more than is available".into(),
+                });
+            }
+            Ok(amount)
+        },
+        DrinkUnit::Percentage => {
+            if amount > 100 {
+                return Err(ContractError::CustomError {
+                    val: "Drink percentage exceeds 100".into(),
+                });
+            }
+            // round up, so drinking a small percentage always drinks something
+            let drunk = (config.current_amount as u16 * amount as u16).div_ceil(100);
+            Ok(drunk as u8)
+        },
+    }
+}
+
+pub fn try_lock(info: MessageInfo, env: Env, deps: DepsMut) -> Result<Response, ContractError> {
+    // an empty potion can't be transferred, releasing it on another chain would get around that
+    if let Some(config) = CONFIG.load(deps.storage)? {
+        if config.is_empty() {
+            return Err(ContractError::PotionEmpty {});
         }
+        check_not_pouring(&config, env.block.time.seconds())?;
     }
+    ownable_common::execute::try_lock(info, env, deps)
 }
 
-pub fn try_transfer(info: MessageInfo, deps: DepsMut, to: Addr) -> Result<Response, ContractError> {
+pub fn try_transfer(info: MessageInfo, env: Env, deps: DepsMut, to: Addr) -> Result<Response, ContractError> {
     let is_locked = LOCKED.load(deps.storage)?;
     if is_locked {
         return Err(ContractError::LockError {
             val: "Unable to transfer a locked ownable".to_string(),
         });
     }
+    if let Some(config) = CONFIG.load(deps.storage)? {
+        if config.is_empty() {
+            return Err(ContractError::PotionEmpty {});
+        }
+        check_not_pouring(&config, env.block.time.seconds())?;
+    }
+    if active_loan(deps.storage, env.block.time.seconds())?.is_some() {
+        return Err(ContractError::CustomError {
+            val: "Unable to transfer a lent ownable".to_string(),
+        });
+    }
+    let to = validate_address(to.as_str(), NETWORK_ID.load(deps.storage)?)?;
+    check_transfer_policy(deps.as_ref(), &to, env.block.time.seconds())?;
+    let previous_owner = OWNABLE_INFO.load(deps.storage)?.owner;
+    let approved = is_approved(deps.storage, &info.sender, env.block.time.seconds())?
+        || sender_matches(deps.storage, &info.sender, &previous_owner)?;
     let ownership = OWNABLE_INFO.update(deps.storage, |mut config| -> Result<_, ContractError> {
-        let address = info.sender.clone();
-        if address != config.owner {
+        if !approved {
             return Err(ContractError::Unauthorized {
                 val: "Unauthorized transfer attempt".to_string(),
             });
         }
-        if address == to {
+        if same_address(&to, &config.owner) {
             return Err(ContractError::CustomError {
                 val: "Unable to transfer: Recipient address is current owner".to_string(),
             });
@@ -266,18 +548,29 @@ pub fn try_transfer(info: MessageInfo, deps: DepsMut, to: Addr) -> Result<Respon
         config.owner = to.clone();
         Ok(config)
     })?;
+    clear_approvals(deps.storage)?;
+    TRANSFER_OFFER.remove(deps.storage);
+    APPROVED_RECIPIENT.remove(deps.storage);
+    LOAN.remove(deps.storage);
+    LAST_TRANSFER.save(deps.storage, &env.block.time.seconds())?;
+    record_history(deps.storage, env.block.height, "transfer", Some(previous_owner), ownership.owner.clone())?;
+
     Ok(Response::new()
         .add_attribute("method", "try_transfer")
         .add_attribute("new_owner", ownership.owner.to_string())
     )
 }
 
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
-        QueryMsg::GetInfo {} => query_ownable_info(deps),
+        QueryMsg::GetInfo {} => query_ownable_info(deps, env),
         QueryMsg::GetMetadata {} => query_ownable_metadata(deps),
         QueryMsg::GetWidgetState {} => query_ownable_widget_state(deps),
         QueryMsg::IsLocked {} => query_lock_state(deps),
+        QueryMsg::IsEventProcessed { event_id } => query_event_processed(deps, event_id),
+        QueryMsg::GetHistory { start_after, limit } => query_history(deps, start_after, limit),
+        QueryMsg::GetApprovals {} => query_approvals(deps, env),
+        QueryMsg::GetTokenUri {} => query_token_uri(deps),
     }
 }
 
@@ -286,23 +579,550 @@ fn query_ownable_widget_state(deps: Deps) -> StdResult<Binary> {
     to_json_binary(&widget_config)
 }
 
-fn query_lock_state(deps: Deps) -> StdResult<Binary> {
-    let is_locked = LOCKED.load(deps.storage)?;
-    to_json_binary(&is_locked)
+fn query_ownable_metadata(deps: Deps) -> StdResult<Binary> {
+    to_json_binary(&load_metadata_response(deps)?)
 }
 
-fn query_ownable_info(deps: Deps) -> StdResult<Binary> {
-    let nft = NFT_ITEM.may_load(deps.storage)?;
-    let ownable_info = OWNABLE_INFO.load(deps.storage)?;
-    to_json_binary(&InfoResponse {
-        owner: ownable_info.owner,
-        issuer: ownable_info.issuer,
-        nft,
-        ownable_type: ownable_info.ownable_type,
+fn query_token_uri(deps: Deps) -> StdResult<Binary> {
+    let metadata = load_metadata_response(deps)?;
+    to_json_binary(&TokenUriResponse {
+        nft: NFT_ITEM.may_load(deps.storage)?,
+        token_uri: token_uri(&metadata)?,
     })
 }
 
-fn query_ownable_metadata(deps: Deps) -> StdResult<Binary> {
-    let meta = METADATA.load(deps.storage)?;
-    to_json_binary(&meta)
+fn load_metadata_response(deps: Deps) -> StdResult<MetadataResponse> {
+    let mut metadata = METADATA.load(deps.storage)?;
+    let config = CONFIG.load(deps.storage)?;
+    let attributes = config.as_ref()
+        .map(metadata_attributes)
+        .unwrap_or_default();
+    // the image is drawn from the current state, replacing any static image data
+    if let Some(config) = &config {
+        metadata.image_data = Some(render_svg(config));
+    }
+    Ok(MetadataResponse { metadata, attributes })
+}
+
+fn metadata_attributes(config: &Config) -> Vec<MetadataAttribute> {
+    vec![
+        MetadataAttribute::new("color", &config.color),
+        MetadataAttribute::new("current_amount", config.current_amount),
+        MetadataAttribute::new("max_capacity", config.max_capacity),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage};
+    use cosmwasm_std::{from_json, OwnedDeps, Timestamp, Uint128};
+    use ownable_std::NFT;
+    use ownable_common::msg::OwnableInfoResponse;
+    use ownable_common::state::{Burn, TransferPolicy, LOCKED_AT};
+    use super::*;
+
+    const ISSUER: &str = "3NBd71MErsjwmStnj8PQECHP1JL2jvuY2HW";
+    const BOB: &str = "3NAigR72mUpT3pqxvpB1YK6L6Eyn5zyRAz5";
+    const CAROL: &str = "3N91FLBZASEAJVnbcjPb2TeUj8WT5dihGeb";
+    const NFT_CONTRACT: &str = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed";
+
+    fn env_at(seconds: u64) -> Env {
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(seconds);
+        env
+    }
+
+    fn drink(amount: u8) -> ExecuteMsg {
+        ExecuteMsg::Drink { amount, unit: DrinkUnit::Absolute }
+    }
+
+    fn instantiate_msg(ownable_id: &str) -> InstantiateMsg {
+        InstantiateMsg {
+            ownable_id: ownable_id.to_string(),
+            package: "package-cid".to_string(),
+            nft: None,
+            ownable_type: None,
+            network_id: b'T',
+            metadata: None,
+            immutable_metadata: None,
+            transfer_policy: None,
+            lock_recovery_delay: None,
+            nft_standard: None,
+        }
+    }
+
+    fn setup(ownable_id: &str) -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+        setup_with(instantiate_msg(ownable_id))
+    }
+
+    fn setup_with(msg: InstantiateMsg) -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+        let mut deps = mock_dependencies();
+        instantiate(deps.as_mut(), mock_env(), mock_info(ISSUER, &[]), msg).unwrap();
+        deps
+    }
+
+    fn transfer(to: &str) -> ExecuteMsg {
+        ExecuteMsg::Transfer { to: Addr::unchecked(to) }
+    }
+
+    fn owner(deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>) -> Addr {
+        OWNABLE_INFO.load(&deps.storage).unwrap().owner
+    }
+
+    fn current_amount(deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>) -> u8 {
+        CONFIG.load(&deps.storage).unwrap().unwrap().current_amount
+    }
+
+    // the event of the given type in the response, as delivered by the wallet
+    fn external_event(res: &Response, event_type: &str, tx_hash: &str) -> ExternalEventMsg {
+        let event = res.events.iter().find(|event| event.ty == event_type).unwrap();
+        let mut attributes: HashMap<String, String> = event.attributes.iter()
+            .map(|attr| (attr.key.clone(), attr.value.clone()))
+            .collect();
+        attributes.insert("tx_hash".to_string(), tx_hash.to_string());
+        attributes.insert("log_index".to_string(), "1".to_string());
+        ExternalEventMsg {
+            network: None,
+            event_type: event.ty.clone(),
+            attributes,
+        }
+    }
+
+    fn pour(amount: u8) -> ExecuteMsg {
+        ExecuteMsg::Pour { target: "potion-2".to_string(), amount }
+    }
+
+    #[test]
+    fn test_pour() {
+        let mut source = setup("potion-1");
+        let mut target = setup("potion-2");
+        let info = mock_info(ISSUER, &[]);
+        execute(target.as_mut(), env_at(1000), info.clone(), drink(50)).unwrap();
+
+        // the poured amount is reserved, but not debited until the target accepts it
+        let res = execute(source.as_mut(), env_at(1000), info.clone(), pour(30)).unwrap();
+        assert_eq!(current_amount(&source), 100);
+        let expires = (1000 + POUR_TIMEOUT).to_string();
+        assert!(res.events[0].attributes.iter().any(|attr| attr.key == "expires" && attr.value == expires));
+        execute(source.as_mut(), env_at(1010), info.clone(), drink(10)).unwrap_err();
+        execute(source.as_mut(), env_at(1010), info.clone(), pour(10)).unwrap_err();
+
+        let source_color = CONFIG.load(&source.storage).unwrap().unwrap().color;
+        let target_color = CONFIG.load(&target.storage).unwrap().unwrap().color;
+        let res = register_external_event(info.clone(), env_at(1010), target.as_mut(), external_event(&res, "pour", "0xabc"), "potion-2".to_string()).unwrap();
+        assert_eq!(current_amount(&target), 80);
+        assert_eq!(
+            CONFIG.load(&target.storage).unwrap().unwrap().color,
+            blend_by_volume(&target_color, 50, &source_color, 30).unwrap(),
+        );
+
+        register_external_event(info.clone(), env_at(1020), source.as_mut(), external_event(&res, "pour_ack", "0xdef"), "potion-1".to_string()).unwrap();
+        assert_eq!(current_amount(&source), 70);
+        assert_eq!(CONFIG.load(&source.storage).unwrap().unwrap().pending, None);
+        execute(source.as_mut(), env_at(1020), info, drink(10)).unwrap();
+    }
+
+    #[test]
+    fn test_pour_replay_refused() {
+        let mut source = setup("potion-1");
+        let mut target = setup("potion-2");
+        let info = mock_info(ISSUER, &[]);
+        execute(target.as_mut(), env_at(1000), info.clone(), drink(50)).unwrap();
+
+        let res = execute(source.as_mut(), env_at(1000), info.clone(), pour(30)).unwrap();
+        let event = external_event(&res, "pour", "0xabc");
+        let is_processed = |deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>| -> bool {
+            let query_msg = QueryMsg::IsEventProcessed { event_id: "0xabc:1".to_string() };
+            from_json(query(deps.as_ref(), mock_env(), query_msg).unwrap()).unwrap()
+        };
+        assert!(!is_processed(&target));
+
+        register_external_event(info.clone(), env_at(1010), target.as_mut(), event.clone(), "potion-2".to_string()).unwrap();
+        assert!(is_processed(&target));
+
+        // the same pour delivered twice only fills the target once
+        let err = register_external_event(info, env_at(1020), target.as_mut(), event, "potion-2".to_string()).unwrap_err();
+        assert!(matches!(err, ContractError::DuplicateEvent { .. }));
+        assert_eq!(current_amount(&target), 80);
+    }
+
+    #[test]
+    fn test_pour_everything() {
+        let mut source = setup("potion-1");
+        let mut target = setup("potion-2");
+        let info = mock_info(ISSUER, &[]);
+        execute(source.as_mut(), env_at(1000), info.clone(), drink(80)).unwrap();
+        execute(target.as_mut(), env_at(1000), info.clone(), drink(50)).unwrap();
+
+        let res = execute(source.as_mut(), env_at(1000), info.clone(), pour(20)).unwrap();
+        assert_eq!(res.events.len(), 1);
+        let res = register_external_event(info.clone(), env_at(1010), target.as_mut(), external_event(&res, "pour", "0xabc"), "potion-2".to_string()).unwrap();
+        let res = register_external_event(info, env_at(1020), source.as_mut(), external_event(&res, "pour_ack", "0xdef"), "potion-1".to_string()).unwrap();
+        assert_eq!(res.events[0].ty, "emptied");
+        assert_eq!(current_amount(&source), 0);
+    }
+
+    #[test]
+    fn test_overflowing_pour_refused() {
+        let mut source = setup("potion-1");
+        let mut target = setup("potion-2");
+        let info = mock_info(ISSUER, &[]);
+
+        let res = execute(source.as_mut(), env_at(1000), info.clone(), pour(30)).unwrap();
+        let res = register_external_event(info.clone(), env_at(1010), target.as_mut(), external_event(&res, "pour", "0xabc"), "potion-2".to_string()).unwrap();
+        assert!(res.events.iter().all(|event| event.ty != "pour_ack"));
+        assert_eq!(current_amount(&target), 100);
+
+        // the source keeps its contents and can pour again right away
+        register_external_event(info.clone(), env_at(1020), source.as_mut(), external_event(&res, "pour_reject", "0xdef"), "potion-1".to_string()).unwrap();
+        assert_eq!(current_amount(&source), 100);
+        assert_eq!(CONFIG.load(&source.storage).unwrap().unwrap().pending, None);
+        execute(source.as_mut(), env_at(1020), info, pour(30)).unwrap();
+    }
+
+    #[test]
+    fn test_pour_times_out() {
+        let mut source = setup("potion-1");
+        let mut target = setup("potion-2");
+        let info = mock_info(ISSUER, &[]);
+        execute(target.as_mut(), env_at(1000), info.clone(), drink(50)).unwrap();
+
+        let res = execute(source.as_mut(), env_at(1000), info.clone(), pour(30)).unwrap();
+
+        // an expired pour is rejected by the target, a late ack is refused by the source
+        let expired = register_external_event(info.clone(), env_at(1000 + POUR_TIMEOUT), target.as_mut(), external_event(&res, "pour", "0xabc"), "potion-2".to_string()).unwrap();
+        assert!(expired.events.iter().any(|event| event.ty == "pour_reject"));
+        assert_eq!(current_amount(&target), 50);
+
+        let ack = PourAckEvent { version: PROTOCOL_VERSION, ownable_id: Some("potion-1".to_string()), target: "potion-2".to_string() };
+        let ack = ExternalEventMsg {
+            network: None,
+            event_type: "pour_ack".to_string(),
+            attributes: ack.to_event().attributes.into_iter()
+                .map(|attr| (attr.key, attr.value))
+                .chain([("tx_hash".to_string(), "0xdef".to_string()), ("log_index".to_string(), "1".to_string())])
+                .collect(),
+        };
+        register_external_event(info.clone(), env_at(1000 + POUR_TIMEOUT), source.as_mut(), ack, "potion-1".to_string()).unwrap_err();
+        assert_eq!(current_amount(&source), 100);
+        execute(source.as_mut(), env_at(1000 + POUR_TIMEOUT), info, drink(10)).unwrap();
+    }
+
+    fn attributes(deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>) -> Vec<MetadataAttribute> {
+        let res: MetadataResponse = from_json(query(deps.as_ref(), mock_env(), QueryMsg::GetMetadata {}).unwrap()).unwrap();
+        res.attributes
+    }
+
+    #[test]
+    fn test_metadata_attributes() {
+        let mut deps = setup("potion-1");
+        let color = CONFIG.load(&deps.storage).unwrap().unwrap().color;
+        assert_eq!(attributes(&deps), vec![
+            MetadataAttribute::new("color", &color),
+            MetadataAttribute::new("current_amount", 100),
+            MetadataAttribute::new("max_capacity", 100),
+        ]);
+
+        // the attributes follow the state of the potion
+        execute(deps.as_mut(), mock_env(), mock_info(ISSUER, &[]), drink(30)).unwrap();
+        assert!(attributes(&deps).contains(&MetadataAttribute::new("current_amount", 70)));
+    }
+
+    #[test]
+    fn test_zero_drink_refused() {
+        let mut deps = setup("potion-1");
+        execute(deps.as_mut(), mock_env(), mock_info(ISSUER, &[]), drink(0)).unwrap_err();
+        let drink = ExecuteMsg::Drink { amount: 0, unit: DrinkUnit::Percentage };
+        execute(deps.as_mut(), mock_env(), mock_info(ISSUER, &[]), drink).unwrap_err();
+        assert_eq!(current_amount(&deps), 100);
+    }
+
+    #[test]
+    fn test_drink_percentage() {
+        let mut deps = setup("potion-1");
+        let drink = |amount| ExecuteMsg::Drink { amount, unit: DrinkUnit::Percentage };
+
+        execute(deps.as_mut(), mock_env(), mock_info(ISSUER, &[]), drink(25)).unwrap();
+        assert_eq!(current_amount(&deps), 75);
+
+        // rounded up, so a small percentage always drinks something
+        execute(deps.as_mut(), mock_env(), mock_info(ISSUER, &[]), drink(1)).unwrap();
+        assert_eq!(current_amount(&deps), 74);
+
+        execute(deps.as_mut(), mock_env(), mock_info(ISSUER, &[]), drink(101)).unwrap_err();
+        execute(deps.as_mut(), mock_env(), mock_info(ISSUER, &[]), drink(100)).unwrap();
+        assert_eq!(current_amount(&deps), 0);
+    }
+
+    #[test]
+    fn test_drink_until_empty() {
+        let mut deps = setup("potion-1");
+        let res = execute(deps.as_mut(), mock_env(), mock_info(ISSUER, &[]), drink(60)).unwrap();
+        assert!(res.events.is_empty());
+        execute(deps.as_mut(), mock_env(), mock_info(ISSUER, &[]), drink(50)).unwrap_err();
+
+        let res = execute(deps.as_mut(), mock_env(), mock_info(ISSUER, &[]), drink(40)).unwrap();
+        assert_eq!(res.events[0].ty, "emptied");
+        assert!(res.events[0].attributes.iter().any(|attr| attr.key == "owner" && attr.value == ISSUER));
+
+        let err = execute(deps.as_mut(), mock_env(), mock_info(ISSUER, &[]), drink(1)).unwrap_err();
+        assert!(matches!(err, ContractError::PotionEmpty {}));
+    }
+
+    #[test]
+    fn test_empty_potion_stays_put() {
+        let mut deps = setup("potion-1");
+        execute(deps.as_mut(), mock_env(), mock_info(ISSUER, &[]), drink(100)).unwrap();
+
+        let transfer = ExecuteMsg::Transfer { to: Addr::unchecked(BOB) };
+        let err = execute(deps.as_mut(), mock_env(), mock_info(ISSUER, &[]), transfer).unwrap_err();
+        assert!(matches!(err, ContractError::PotionEmpty {}));
+
+        let err = execute(deps.as_mut(), mock_env(), mock_info(ISSUER, &[]), ExecuteMsg::Lock {}).unwrap_err();
+        assert!(matches!(err, ContractError::PotionEmpty {}));
+        assert!(!LOCKED.load(&deps.storage).unwrap());
+    }
+
+    #[test]
+    fn test_soulbound_potion_stays_put() {
+        let mut deps = setup_with(InstantiateMsg {
+            transfer_policy: Some(TransferPolicy::Soulbound),
+            ..instantiate_msg("potion-1")
+        });
+
+        let err = execute(deps.as_mut(), mock_env(), mock_info(ISSUER, &[]), transfer(BOB)).unwrap_err();
+        assert!(matches!(err, ContractError::TransferPolicyError { .. }));
+        let err = execute(deps.as_mut(), mock_env(), mock_info(ISSUER, &[]), ExecuteMsg::Lock {}).unwrap_err();
+        assert!(matches!(err, ContractError::TransferPolicyError { .. }));
+
+        // the owner can still drink it
+        execute(deps.as_mut(), mock_env(), mock_info(ISSUER, &[]), drink(10)).unwrap();
+    }
+
+    #[test]
+    fn test_transfer_with_issuer_approval() {
+        let mut deps = setup_with(InstantiateMsg {
+            transfer_policy: Some(TransferPolicy::IssuerApproval),
+            ..instantiate_msg("potion-1")
+        });
+        execute(deps.as_mut(), mock_env(), mock_info(ISSUER, &[]), transfer(BOB)).unwrap_err();
+
+        let approve = ExecuteMsg::ApproveTransfer { to: Addr::unchecked(BOB) };
+        execute(deps.as_mut(), mock_env(), mock_info(BOB, &[]), approve.clone()).unwrap_err();
+        execute(deps.as_mut(), mock_env(), mock_info(ISSUER, &[]), approve).unwrap();
+
+        let err = execute(deps.as_mut(), mock_env(), mock_info(ISSUER, &[]), transfer(CAROL)).unwrap_err();
+        assert!(matches!(err, ContractError::TransferPolicyError { .. }));
+        execute(deps.as_mut(), mock_env(), mock_info(ISSUER, &[]), transfer(BOB)).unwrap();
+        assert_eq!(owner(&deps), BOB);
+
+        // the approval is used up by the transfer
+        execute(deps.as_mut(), mock_env(), mock_info(BOB, &[]), transfer(CAROL)).unwrap_err();
+    }
+
+    #[test]
+    fn test_transfer_cooldown() {
+        let mut deps = setup_with(InstantiateMsg {
+            transfer_policy: Some(TransferPolicy::Cooldown { seconds: 100 }),
+            ..instantiate_msg("potion-1")
+        });
+        let now = mock_env().block.time.seconds();
+
+        let err = execute(deps.as_mut(), env_at(now + 99), mock_info(ISSUER, &[]), transfer(BOB)).unwrap_err();
+        assert!(matches!(err, ContractError::TransferPolicyError { .. }));
+        execute(deps.as_mut(), env_at(now + 100), mock_info(ISSUER, &[]), transfer(BOB)).unwrap();
+
+        execute(deps.as_mut(), env_at(now + 150), mock_info(BOB, &[]), transfer(CAROL)).unwrap_err();
+        execute(deps.as_mut(), env_at(now + 200), mock_info(BOB, &[]), transfer(CAROL)).unwrap();
+        assert_eq!(owner(&deps), CAROL);
+    }
+
+    #[test]
+    fn test_burn() {
+        let mut source = setup("potion-1");
+        let mut deps = setup("potion-2");
+        let now = mock_env().block.time.seconds();
+        execute(deps.as_mut(), env_at(now), mock_info(ISSUER, &[]), drink(50)).unwrap();
+
+        execute(deps.as_mut(), env_at(now), mock_info(BOB, &[]), ExecuteMsg::Burn {}).unwrap_err();
+        let res = execute(deps.as_mut(), env_at(now + 10), mock_info(ISSUER, &[]), ExecuteMsg::Burn {}).unwrap();
+        assert!(res.events.iter().any(|event| event.ty == "burn"));
+
+        let info: OwnableInfoResponse = from_json(query(deps.as_ref(), mock_env(), QueryMsg::GetInfo {}).unwrap()).unwrap();
+        assert_eq!(info.burned, Some(Burn { burned_by: Addr::unchecked(ISSUER), time: now + 10 }));
+
+        // a burned potion can't be drunk or poured into
+        let err = execute(deps.as_mut(), env_at(now + 20), mock_info(ISSUER, &[]), drink(10)).unwrap_err();
+        assert!(matches!(err, ContractError::Burned {}));
+        let res = execute(source.as_mut(), env_at(now + 20), mock_info(ISSUER, &[]), pour(10)).unwrap();
+        let err = register_external_event(mock_info(ISSUER, &[]), env_at(now + 20), deps.as_mut(), external_event(&res, "pour", "0xabc"), "potion-2".to_string()).unwrap_err();
+        assert!(matches!(err, ContractError::Burned {}));
+        assert_eq!(current_amount(&deps), 50);
+    }
+
+    #[test]
+    fn test_burn_lent_potion_refused() {
+        let mut deps = setup("potion-1");
+        let now = mock_env().block.time.seconds();
+        let lend = ExecuteMsg::Lend { to: Addr::unchecked(BOB), until: now + 100 };
+        execute(deps.as_mut(), env_at(now), mock_info(ISSUER, &[]), lend).unwrap();
+
+        execute(deps.as_mut(), env_at(now + 10), mock_info(ISSUER, &[]), ExecuteMsg::Burn {}).unwrap_err();
+        execute(deps.as_mut(), env_at(now + 10), mock_info(BOB, &[]), ExecuteMsg::Burn {}).unwrap_err();
+        assert!(BURNED.may_load(&deps.storage).unwrap().is_none());
+        execute(deps.as_mut(), env_at(now + 100), mock_info(ISSUER, &[]), ExecuteMsg::Burn {}).unwrap();
+    }
+
+    #[test]
+    fn test_lock_recovery_delay() {
+        let mut deps = setup_with(InstantiateMsg { lock_recovery_delay: Some(100), ..instantiate_msg("potion-1") });
+        execute(deps.as_mut(), env_at(1000), mock_info(ISSUER, &[]), transfer(BOB)).unwrap();
+        execute(deps.as_mut(), env_at(1000), mock_info(BOB, &[]), ExecuteMsg::Lock {}).unwrap();
+
+        let err = execute(deps.as_mut(), env_at(1099), mock_info(ISSUER, &[]), ExecuteMsg::RecoverLock {}).unwrap_err();
+        assert!(matches!(err, ContractError::LockError { .. }));
+        let err = execute(deps.as_mut(), env_at(1100), mock_info(BOB, &[]), ExecuteMsg::RecoverLock {}).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized { .. }));
+
+        execute(deps.as_mut(), env_at(1100), mock_info(ISSUER, &[]), ExecuteMsg::RecoverLock {}).unwrap();
+        assert!(!LOCKED.load(&deps.storage).unwrap());
+        assert_eq!(owner(&deps), BOB);
+        execute(deps.as_mut(), env_at(1100), mock_info(BOB, &[]), drink(10)).unwrap();
+    }
+
+    #[test]
+    fn test_legacy_lock_not_recoverable() {
+        let mut deps = setup_with(InstantiateMsg { lock_recovery_delay: Some(100), ..instantiate_msg("potion-1") });
+        execute(deps.as_mut(), env_at(1000), mock_info(ISSUER, &[]), ExecuteMsg::Lock {}).unwrap();
+        // locked before the lock time was stored
+        LOCKED_AT.remove(&mut deps.storage);
+
+        let err = execute(deps.as_mut(), env_at(1_000_000), mock_info(ISSUER, &[]), ExecuteMsg::RecoverLock {}).unwrap_err();
+        assert!(matches!(err, ContractError::LockError { .. }));
+        assert!(LOCKED.load(&deps.storage).unwrap());
+    }
+
+    #[test]
+    fn test_transfer_invalid_address() {
+        let mut deps = setup("potion-1");
+        let err = execute(deps.as_mut(), env_at(1000), mock_info(ISSUER, &[]), transfer("bob")).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidAddress { .. }));
+        let lend = ExecuteMsg::Lend { to: Addr::unchecked("0x7e5f"), until: 2000 };
+        let err = execute(deps.as_mut(), env_at(1000), mock_info(ISSUER, &[]), lend).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidAddress { .. }));
+        assert_eq!(owner(&deps), ISSUER);
+    }
+
+    #[test]
+    fn test_transfer_to_checksummed_address() {
+        let mut deps = setup("potion-1");
+        execute(deps.as_mut(), env_at(1000), mock_info(ISSUER, &[]), transfer("0x7e5f4552091a69125d5dfcb7b8c2659029395bdf")).unwrap();
+        assert_eq!(owner(&deps), "0x7E5F4552091A69125d5DfCb7b8C2659029395Bdf");
+    }
+
+    #[test]
+    fn test_drink_with_matched_address() {
+        let mut deps = setup("potion-1");
+        execute(deps.as_mut(), env_at(1000), mock_info(ISSUER, &[]), transfer("0x7e5f4552091a69125d5dfcb7b8c2659029395bdf")).unwrap();
+
+        // the owner is an Ethereum address, the sender signs with its public key
+        execute(deps.as_mut(), env_at(1000), mock_info(BOB, &[]), drink(10)).unwrap_err();
+        let info = mock_info("jesTu2BpszP8DKSoi1R5G6ggjHrsrVnboLdx6V47vkoR", &[]);
+        execute(deps.as_mut(), env_at(1000), info, drink(10)).unwrap();
+        assert_eq!(current_amount(&deps), 90);
+    }
+
+    #[test]
+    fn test_erc1155_lock_amount() {
+        let nft = NFT {
+            network: "eip155:1".to_string(),
+            id: Uint128::new(1),
+            address: NFT_CONTRACT.to_string(),
+            lock_service: None,
+        };
+        let mut deps = setup_with(InstantiateMsg {
+            nft: Some(nft),
+            nft_standard: Some(NftStandard::Erc1155 { amount: Uint128::new(5) }),
+            ..instantiate_msg("potion-1")
+        });
+        execute(deps.as_mut(), env_at(1000), mock_info(ISSUER, &[]), ExecuteMsg::Lock {}).unwrap();
+
+        let attributes = [
+            ("owner", BOB),
+            ("token_id", "1"),
+            ("contract", NFT_CONTRACT),
+            ("tx_hash", "0xabc"),
+            ("log_index", "0"),
+        ];
+        let mut event = ExternalEventMsg {
+            network: Some("eip155:1".to_string()),
+            event_type: "lock".to_string(),
+            attributes: attributes.iter().map(|(key, value)| (key.to_string(), value.to_string())).collect(),
+        };
+        let err = register_external_event(mock_info(BOB, &[]), env_at(2000), deps.as_mut(), event.clone(), "potion-1".to_string()).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidExternalEventArgs {}));
+
+        // the locked amount has to be exactly the amount the potion is linked to
+        for amount in ["4", "6"] {
+            event.attributes.insert("amount".to_string(), amount.to_string());
+            let err = register_external_event(mock_info(BOB, &[]), env_at(2000), deps.as_mut(), event.clone(), "potion-1".to_string()).unwrap_err();
+            assert!(matches!(err, ContractError::LockError { .. }));
+        }
+        assert_eq!(owner(&deps), ISSUER);
+
+        event.attributes.insert("amount".to_string(), "5".to_string());
+        register_external_event(mock_info(BOB, &[]), env_at(2000), deps.as_mut(), event, "potion-1".to_string()).unwrap();
+        assert_eq!(owner(&deps), BOB);
+        execute(deps.as_mut(), env_at(2000), mock_info(BOB, &[]), drink(10)).unwrap();
+    }
+
+    #[test]
+    fn test_instantiate_invalid_metadata() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            ownable_id: "potion-1".to_string(),
+            package: "package-cid".to_string(),
+            nft: None,
+            ownable_type: None,
+            network_id: b'T',
+            metadata: Some(Metadata { image: Some("potion.png".to_string()), ..Default::default() }),
+            immutable_metadata: None,
+            transfer_policy: None,
+            lock_recovery_delay: None,
+            nft_standard: None,
+        };
+        let err = instantiate(deps.as_mut(), mock_env(), mock_info(ISSUER, &[]), msg).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidMetadata { .. }));
+    }
+
+    #[test]
+    fn test_borrower_drinks() {
+        let mut deps = setup("potion-1");
+        let now = mock_env().block.time.seconds();
+        let lend = ExecuteMsg::Lend { to: Addr::unchecked(BOB), until: now + 100 };
+        execute(deps.as_mut(), env_at(now), mock_info(ISSUER, &[]), lend).unwrap();
+
+        // the borrower has the user rights until the loan ends
+        execute(deps.as_mut(), env_at(now + 10), mock_info(ISSUER, &[]), drink(10)).unwrap_err();
+        execute(deps.as_mut(), env_at(now + 10), mock_info(BOB, &[]), drink(10)).unwrap();
+        assert_eq!(current_amount(&deps), 90);
+
+        execute(deps.as_mut(), env_at(now + 100), mock_info(BOB, &[]), drink(10)).unwrap_err();
+        execute(deps.as_mut(), env_at(now + 100), mock_info(ISSUER, &[]), drink(10)).unwrap();
+        assert_eq!(current_amount(&deps), 80);
+    }
+
+    #[test]
+    fn test_borrower_returns_early() {
+        let mut deps = setup("potion-1");
+        let now = mock_env().block.time.seconds();
+        let lend = ExecuteMsg::Lend { to: Addr::unchecked(BOB), until: now + 100 };
+        execute(deps.as_mut(), env_at(now), mock_info(ISSUER, &[]), lend.clone()).unwrap();
+        execute(deps.as_mut(), env_at(now), mock_info(ISSUER, &[]), lend).unwrap_err();
+
+        let res = execute(deps.as_mut(), env_at(now + 10), mock_info(BOB, &[]), ExecuteMsg::Reclaim {}).unwrap();
+        assert!(res.attributes.iter().any(|attr| attr.key == "expired" && attr.value == "false"));
+        execute(deps.as_mut(), env_at(now + 10), mock_info(ISSUER, &[]), drink(10)).unwrap();
+    }
 }
@@ -0,0 +1,143 @@
+use cosmwasm_std::{Addr, Event};
+use ownable_std::ExternalEventMsg;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use ownable_common::error::ContractError;
+use ownable_common::event::{optional_attribute, parse_version, required_attribute, ExternalEvent};
+
+// version of the pour protocol implemented by this ownable
+pub const PROTOCOL_VERSION: u8 = 1;
+// seconds after which an unacknowledged pour is rolled back
+pub const POUR_TIMEOUT: u64 = 3600;
+
+/// Emitted by a potion that is poured into another potion
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PourEvent {
+    pub version: u8,
+    // None for potions instantiated before their id was stored
+    pub ownable_id: Option<String>,
+    pub target: String,
+    pub issuer: Addr,
+    pub owner: Addr,
+    pub amount: u8,
+    pub color: String,
+    // block time in seconds at which the source rolls back, None if it never does
+    pub expires: Option<u64>,
+}
+
+impl PourEvent {
+    pub fn is_expired(&self, now: u64) -> bool {
+        self.expires.is_some_and(|expires| now >= expires)
+    }
+}
+
+impl ExternalEvent for PourEvent {
+    const EVENT_TYPE: &'static str = "pour";
+
+    fn to_event(&self) -> Event {
+        let mut event = Event::new(Self::EVENT_TYPE)
+            .add_attribute("version", self.version.to_string())
+            .add_attribute("target", self.target.clone())
+            .add_attribute("issuer", self.issuer.to_string())
+            .add_attribute("owner", self.owner.to_string())
+            .add_attribute("amount", self.amount.to_string())
+            .add_attribute("color", self.color.clone());
+        if let Some(ownable_id) = &self.ownable_id {
+            event = event.add_attribute("ownable_id", ownable_id);
+        }
+        if let Some(expires) = self.expires {
+            event = event.add_attribute("expires", expires.to_string());
+        }
+        event
+    }
+
+    fn from_external_event(event: &ExternalEventMsg) -> Result<Self, ContractError> {
+        let version = parse_version(event, Self::EVENT_TYPE, PROTOCOL_VERSION)?;
+        let amount = required_attribute(event, "amount")?
+            .parse::<u8>()
+            .map_err(|_| ContractError::InvalidExternalEventArgs {})?;
+
+        Ok(PourEvent {
+            version,
+            ownable_id: optional_attribute(event, "ownable_id"),
+            target: required_attribute(event, "target")?,
+            issuer: Addr::unchecked(required_attribute(event, "issuer")?),
+            owner: Addr::unchecked(required_attribute(event, "owner")?),
+            amount,
+            color: required_attribute(event, "color")?,
+            expires: optional_attribute(event, "expires")
+                .map(|expires| expires.parse::<u64>().map_err(|_| ContractError::InvalidExternalEventArgs {}))
+                .transpose()?,
+        })
+    }
+}
+
+/// Returned by a target potion that accepted a pour, the source is debited
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PourAckEvent {
+    pub version: u8,
+    // id of the source potion
+    pub ownable_id: Option<String>,
+    pub target: String,
+}
+
+impl ExternalEvent for PourAckEvent {
+    const EVENT_TYPE: &'static str = "pour_ack";
+
+    fn to_event(&self) -> Event {
+        let mut event = Event::new(Self::EVENT_TYPE)
+            .add_attribute("version", self.version.to_string())
+            .add_attribute("target", self.target.clone());
+        if let Some(ownable_id) = &self.ownable_id {
+            event = event.add_attribute("ownable_id", ownable_id);
+        }
+        event
+    }
+
+    fn from_external_event(event: &ExternalEventMsg) -> Result<Self, ContractError> {
+        let version = parse_version(event, Self::EVENT_TYPE, PROTOCOL_VERSION)?;
+
+        Ok(PourAckEvent {
+            version,
+            ownable_id: optional_attribute(event, "ownable_id"),
+            target: required_attribute(event, "target")?,
+        })
+    }
+}
+
+/// Returned by a target potion that refused a pour, the source keeps its contents
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PourRejectEvent {
+    pub version: u8,
+    // id of the source potion
+    pub ownable_id: Option<String>,
+    pub target: String,
+    pub reason: String,
+}
+
+impl ExternalEvent for PourRejectEvent {
+    const EVENT_TYPE: &'static str = "pour_reject";
+
+    fn to_event(&self) -> Event {
+        let mut event = Event::new(Self::EVENT_TYPE)
+            .add_attribute("version", self.version.to_string())
+            .add_attribute("target", self.target.clone())
+            .add_attribute("reason", self.reason.clone());
+        if let Some(ownable_id) = &self.ownable_id {
+            event = event.add_attribute("ownable_id", ownable_id);
+        }
+        event
+    }
+
+    fn from_external_event(event: &ExternalEventMsg) -> Result<Self, ContractError> {
+        let version = parse_version(event, Self::EVENT_TYPE, PROTOCOL_VERSION)?;
+
+        Ok(PourRejectEvent {
+            version,
+            ownable_id: optional_attribute(event, "ownable_id"),
+            target: required_attribute(event, "target")?,
+            reason: optional_attribute(event, "reason").unwrap_or_default(),
+        })
+    }
+}
+
@@ -0,0 +1,46 @@
+use crate::state::Config;
+
+const FALLBACK_COLOR: &str = "#000000";
+
+// the liquid fills the round part of the flask, 120 units high
+const LIQUID_TOP: u32 = 65;
+const LIQUID_HEIGHT: u32 = 120;
+
+/// Render the potion as SVG, with the liquid level matching the current amount
+pub fn render_svg(config: &Config) -> String {
+    let color = safe_color(&config.color);
+    let height = match config.max_capacity {
+        0 => 0,
+        max => LIQUID_HEIGHT * config.current_amount.min(max) as u32 / max as u32,
+    };
+
+    let mut svg = String::from(r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 200 200">"#);
+    svg.push_str(r#"<defs><clipPath id="flask"><circle cx="100" cy="125" r="60"/></clipPath></defs>"#);
+
+    // liquid
+    svg.push_str(&format!(
+        r#"<rect x="40" y="{}" width="120" height="{}" fill="{}" clip-path="url(#flask)"/>"#,
+        LIQUID_TOP + LIQUID_HEIGHT - height, height, color,
+    ));
+
+    // glass and cork
+    svg.push_str(r##"<circle cx="100" cy="125" r="60" fill="none" stroke="#4a4a4a" stroke-width="5"/>"##);
+    svg.push_str(r##"<rect x="82" y="35" width="36" height="34" fill="#ffffff" fill-opacity="0.4" stroke="#4a4a4a" stroke-width="5"/>"##);
+    svg.push_str(r##"<rect x="85" y="18" width="30" height="17" rx="4" fill="#b5835a" stroke="#4a4a4a" stroke-width="5"/>"##);
+
+    svg.push_str(&format!(
+        r##"<text x="100" y="135" text-anchor="middle" font-family="sans-serif" font-size="24" fill="#000000">{}</text>"##,
+        config.current_amount,
+    ));
+
+    svg.push_str("</svg>");
+    svg
+}
+
+// only hex colors are written into the svg, anything else could inject markup
+fn safe_color(color: &str) -> &str {
+    let valid = color.len() == 7
+        && color.starts_with('#')
+        && color[1..].chars().all(|c| c.is_ascii_hexdigit());
+    if valid { color } else { FALLBACK_COLOR }
+}
@@ -1,7 +1,8 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use cosmwasm_std::{Addr, StdResult, Storage};
 use cw_storage_plus::Item;
-use ownable_std::{Metadata, NFT, OwnableInfo};
+use ownable_common::state::active_loan;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Config {
@@ -9,12 +10,37 @@ pub struct Config {
     pub max_capacity: u8,
     pub current_amount: u8,
     pub color: String,
+    // set while waiting for the target to acknowledge a pour
+    #[serde(default)]
+    pub pending: Option<PendingPour>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingPour {
+    pub target: String,
+    pub amount: u8,
+    // block time in seconds at which the pour event was emitted
+    pub since: u64,
+}
+
+impl Config {
+    // an empty potion can't be drunk or transferred until it's refilled
+    pub fn is_empty(&self) -> bool {
+        self.current_amount == 0
+    }
+
+    pub fn is_pending(&self, now: u64, timeout: u64) -> bool {
+        match &self.pending {
+            None => false,
+            Some(pending) => now < pending.since + timeout,
+        }
+    }
 }
 
 pub const CONFIG: Item<Option<Config>> = Item::new("config");
-pub const OWNABLE_INFO: Item<OwnableInfo> = Item::new("ownable_info");
-pub const METADATA: Item<Metadata> = Item::new("metadata");
-pub const NFT_ITEM: Item<NFT> = Item::new("nft");
-pub const LOCKED: Item<bool> = Item::new("is_locked");
-pub const PACKAGE_CID: Item<String> = Item::new("package_cid");
-pub const NETWORK_ID: Item<u8> = Item::new("network_id");
+
+/// The borrower while the ownable is lent out, otherwise the owner
+pub fn current_user(storage: &dyn Storage, owner: &Addr, now: u64) -> StdResult<Addr> {
+    Ok(active_loan(storage, now)?.map_or_else(|| owner.clone(), |loan| loan.borrower))
+}
+
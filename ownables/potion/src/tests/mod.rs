@@ -1,3 +0,0 @@
-mod tests;
-mod integration_tests;
-mod store;
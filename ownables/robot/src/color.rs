@@ -0,0 +1,98 @@
+use ownable_common::error::ContractError;
+use ownable_common::event::BlendMode;
+
+type Rgb = (u8, u8, u8);
+
+/// Blend the color of a paint into the current color, using integer math only
+pub fn blend_color(current: &str, paint: &str, blend: &BlendMode) -> Result<String, ContractError> {
+    let current = parse_hex(current)?;
+    let paint = parse_hex(paint)?;
+
+    let rgb = match blend {
+        BlendMode::Replace => paint,
+        BlendMode::Mix { ratio } => mix(current, paint, *ratio),
+        BlendMode::Tint { amount } => mix(current, (255, 255, 255), *amount),
+        BlendMode::Shade { amount } => mix(current, (0, 0, 0), *amount),
+    };
+
+    Ok(format_hex(rgb))
+}
+
+/// Lighten a color towards white
+pub fn tint(color: &str, amount: u8) -> Result<String, ContractError> {
+    Ok(format_hex(mix(parse_hex(color)?, (255, 255, 255), amount)))
+}
+
+/// Move a color towards the gray of the same luminance
+pub fn desaturate(color: &str, amount: u8) -> Result<String, ContractError> {
+    let rgb = parse_hex(color)?;
+    let luma = ((rgb.0 as u32 * 299 + rgb.1 as u32 * 587 + rgb.2 as u32 * 114) / 1000) as u8;
+    Ok(format_hex(mix(rgb, (luma, luma, luma), amount)))
+}
+
+// percentage is the share of `other` in the result
+fn mix(base: Rgb, other: Rgb, percentage: u8) -> Rgb {
+    let p = percentage.min(100) as u32;
+    let channel = |a: u8, b: u8| -> u8 {
+        // round half up
+        ((a as u32 * (100 - p) + b as u32 * p + 50) / 100) as u8
+    };
+    (channel(base.0, other.0), channel(base.1, other.1), channel(base.2, other.2))
+}
+
+fn parse_hex(color: &str) -> Result<Rgb, ContractError> {
+    let invalid = || ContractError::CustomError { val: format!("Invalid color: {}", color) };
+
+    let hex = color.strip_prefix('#').ok_or_else(invalid)?;
+    if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(invalid());
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).map_err(|_| invalid());
+
+    Ok((channel(0)?, channel(2)?, channel(4)?))
+}
+
+fn format_hex((r, g, b): Rgb) -> String {
+    format!("#{:02X}{:02X}{:02X}", r, g, b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blend_color() {
+        let blend = |current, paint, mode| blend_color(current, paint, &mode).unwrap();
+        assert_eq!(blend("#000000", "#ff8000", BlendMode::Replace), "#FF8000");
+        assert_eq!(blend("#000000", "#FF8000", BlendMode::Mix { ratio: 50 }), "#804000");
+        assert_eq!(blend("#000000", "#FF8000", BlendMode::Tint { amount: 50 }), "#808080");
+        assert_eq!(blend("#FFFFFF", "#FF8000", BlendMode::Shade { amount: 100 }), "#000000");
+
+        blend_color("#000000", "ff8000", &BlendMode::Replace).unwrap_err();
+        blend_color("#0000", "#FF8000", &BlendMode::Replace).unwrap_err();
+        blend_color("#00000g", "#FF8000", &BlendMode::Replace).unwrap_err();
+    }
+
+    #[test]
+    fn test_mix() {
+        assert_eq!(mix((0, 0, 0), (255, 128, 1), 0), (0, 0, 0));
+        // rounded half up
+        assert_eq!(mix((0, 0, 0), (255, 128, 1), 50), (128, 64, 1));
+        assert_eq!(mix((0, 0, 0), (255, 128, 1), 100), (255, 128, 1));
+        assert_eq!(mix((0, 0, 0), (255, 128, 1), 150), (255, 128, 1));
+    }
+
+    #[test]
+    fn test_tint() {
+        assert_eq!(tint("#FF0000", 50).unwrap(), "#FF8080");
+        assert_eq!(tint("#FF0000", 100).unwrap(), "#FFFFFF");
+        tint("red", 50).unwrap_err();
+    }
+
+    #[test]
+    fn test_desaturate() {
+        assert_eq!(desaturate("#ff0000", 0).unwrap(), "#FF0000");
+        assert_eq!(desaturate("#FF0000", 100).unwrap(), "#4C4C4C");
+        assert_eq!(desaturate("#808080", 60).unwrap(), "#808080");
+    }
+}
@@ -1,11 +1,22 @@
-use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
-use crate::state::{NFT_ITEM, Config, CONFIG, METADATA, LOCKED, PACKAGE_CID, OWNABLE_INFO, NETWORK_ID};
-use cosmwasm_std::{to_json_binary, Binary};
+use crate::msg::{AllowedIssuers, ConsumePolicyResponse, ExecuteMsg, InstantiateMsg, QueryMsg, SlotConfig};
+use ownable_common::msg::{MetadataResponse, MetadataAttribute, TokenUriResponse};
+use crate::state::{Config, CONFIG, CONSUME_POLICY, ColorChange, Equipment, Slot, default_color_types, default_slots, is_allowed_issuer, load_config, migrate_config};
+use ownable_common::state::{NFT_ITEM, METADATA, LOCKED, PACKAGE_CID, OWNABLE_INFO, NETWORK_ID, MetadataLock, METADATA_LOCK, clear_approvals, is_approved, record_history, TRANSFER_OFFER, TRANSFER_POLICY, APPROVED_RECIPIENT, LAST_TRANSFER, LOAN, active_loan, BURNED, LOCK_RECOVERY_DELAY, DEFAULT_LOCK_RECOVERY_DELAY, PROCESSED_EVENTS, external_event_id, NftStandard, NFT_STANDARD, OWNABLE_ID};
+use cosmwasm_std::{to_json_binary, Binary, Order};
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::{Addr, Deps, DepsMut, Env, MessageInfo, Response, StdResult};
 use cw2::set_contract_version;
-use ownable_std::{ExternalEventMsg, InfoResponse, Metadata, OwnableInfo, rgb_hex};
-use crate::error::ContractError;
+use ownable_std::{ExternalEventMsg, Metadata, OwnableInfo, rgb_hex};
+use crate::color::blend_color;
+use ownable_common::error::ContractError;
+use ownable_common::execute::{try_register_lock, try_lock, try_update_metadata, try_freeze_metadata, try_approve, try_revoke, try_approve_all, try_revoke_all, try_offer_transfer, try_accept_transfer, try_cancel_offer, try_approve_transfer, check_transfer_policy, try_lend, try_reclaim, try_burn, try_recover_lock};
+use ownable_common::query::{query_event_processed, query_lock_state, query_ownable_info, query_approvals, query_history};
+use ownable_common::nft::AssetId;
+use ownable_common::identity::sender_matches;
+use ownable_common::address::{same_address, validate_address};
+use crate::render::render_svg;
+use ownable_common::metadata::{merge_metadata, validate_immutable_fields, validate_metadata, token_uri};
+use ownable_common::event::{ConsumeAckEvent, ConsumeEvent, ConsumeRejectEvent, ExternalEvent, RestoreEvent, PROTOCOL_VERSION};
 
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:ownable-robot";
@@ -13,7 +24,7 @@ const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 pub fn instantiate(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
@@ -25,15 +36,20 @@ pub fn instantiate(
         ownable_type: Some("robot".to_string()),
     };
 
+    let slots = match msg.slots {
+        None => default_slots(),
+        Some(slots) => build_slots(slots)?,
+    };
+
     let config = Config {
         consumed_ownable_ids: vec![],
         color: rgb_hex(25, 82, 114),
-        has_antenna: false,
-        has_speaker: false,
-        has_armor: false
+        slots,
+        color_types: msg.color_types.unwrap_or_else(default_color_types),
+        color_history: vec![],
     };
 
-    let meta = Metadata {
+    let meta = merge_metadata(Metadata {
         image: None,
         image_data: None,
         external_url: None,
@@ -42,16 +58,35 @@ pub fn instantiate(
         background_color: None,
         animation_url: None,
         youtube_url: None,
+    }, msg.metadata.clone());
+    validate_metadata(&meta)?;
+    let metadata_lock = MetadataLock {
+        frozen: false,
+        immutable_fields: msg.immutable_metadata.clone().unwrap_or_default(),
     };
+    validate_immutable_fields(&metadata_lock.immutable_fields)?;
 
     NETWORK_ID.save(deps.storage, &msg.network_id)?;
+    OWNABLE_ID.save(deps.storage, &msg.ownable_id)?;
     CONFIG.save(deps.storage, &Some(config.clone()))?;
     if let Some(nft) = msg.nft {
+        // links without an explicit standard predate CAIP-19 and aren't validated
+        if let Some(standard) = &msg.nft_standard {
+            AssetId::from_nft(&nft, standard)?.validate()?;
+        }
+        let standard = msg.nft_standard.clone()
+            .unwrap_or_else(|| NftStandard::for_network(&nft.network));
         NFT_ITEM.save(deps.storage, &nft)?;
+        NFT_STANDARD.save(deps.storage, &standard)?;
     }
     METADATA.save(deps.storage, &meta)?;
+    METADATA_LOCK.save(deps.storage, &metadata_lock)?;
+    TRANSFER_POLICY.save(deps.storage, &msg.transfer_policy.clone().unwrap_or_default())?;
+    LOCK_RECOVERY_DELAY.save(deps.storage, &msg.lock_recovery_delay.unwrap_or(DEFAULT_LOCK_RECOVERY_DELAY))?;
+    LAST_TRANSFER.save(deps.storage, &env.block.time.seconds())?;
     LOCKED.save(deps.storage, &false)?;
     OWNABLE_INFO.save(deps.storage, &ownable_info)?;
+    record_history(deps.storage, env.block.height, "instantiate", None, ownable_info.owner.clone())?;
     PACKAGE_CID.save(deps.storage, &msg.package)?;
 
     Ok(Response::new()
@@ -62,239 +97,350 @@ pub fn instantiate(
     )
 }
 
+fn build_slots(slots: Vec<SlotConfig>) -> Result<Vec<Slot>, ContractError> {
+    let mut built: Vec<Slot> = vec![];
+    for slot in slots {
+        if slot.name.is_empty() || slot.accepts.is_empty() {
+            return Err(ContractError::CustomError {
+                val: "A slot needs a name and at least one consumable type".to_string(),
+            });
+        }
+        if built.iter().any(|s| s.name == slot.name) {
+            return Err(ContractError::CustomError {
+                val: format!("Duplicate slot: {}", slot.name),
+            });
+        }
+        built.push(Slot {
+            name: slot.name,
+            accepts: slot.accepts,
+            equipped: None,
+        });
+    }
+    Ok(built)
+}
+
 pub fn execute(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
+    if BURNED.may_load(deps.storage)?.is_some() {
+        return Err(ContractError::Burned {});
+    }
+    migrate_config(deps.storage)?;
     match msg {
-        ExecuteMsg::Transfer { to } => try_transfer(info, deps, to),
-        ExecuteMsg::Lock {} => try_lock(info, deps),
+        ExecuteMsg::Transfer { to } => try_transfer(info, env, deps, to),
+        ExecuteMsg::Lock {} => try_lock(info, env, deps),
+        ExecuteMsg::RecoverLock {} => try_recover_lock(info, env, deps),
+        ExecuteMsg::Burn {} => try_burn(info, env, deps),
+        ExecuteMsg::Lend { to, until } => try_lend(info, env, deps, to, until),
+        ExecuteMsg::Reclaim {} => try_reclaim(info, env, deps),
+        ExecuteMsg::ApproveTransfer { to } => try_approve_transfer(info, deps, to),
+        ExecuteMsg::OfferTransfer { to, expires } => try_offer_transfer(info, env, deps, to, expires),
+        ExecuteMsg::AcceptTransfer {} => try_accept_transfer(info, env, deps, try_transfer),
+        ExecuteMsg::CancelOffer {} => try_cancel_offer(info, deps),
+        ExecuteMsg::Approve { spender, expires } => try_approve(info, env, deps, spender, expires),
+        ExecuteMsg::Revoke { spender } => try_revoke(info, env, deps, spender),
+        ExecuteMsg::ApproveAll { operator, expires } => try_approve_all(info, env, deps, operator, expires),
+        ExecuteMsg::RevokeAll { operator } => try_revoke_all(info, deps, operator),
+        ExecuteMsg::UpdateMetadata { metadata } => try_update_metadata(info, deps, metadata),
+        ExecuteMsg::FreezeMetadata {} => try_freeze_metadata(info, deps),
+        ExecuteMsg::Detach { slot } => try_detach(info, deps, slot),
+        ExecuteMsg::AllowIssuer { consumable_type, issuer } =>
+            try_set_issuer_allowed(info, deps, consumable_type, issuer, true),
+        ExecuteMsg::DisallowIssuer { consumable_type, issuer } =>
+            try_set_issuer_allowed(info, deps, consumable_type, issuer, false),
     }
 }
 
 pub fn register_external_event(
     info: MessageInfo,
-    deps: DepsMut,
+    env: Env,
+    mut deps: DepsMut,
     event: ExternalEventMsg,
     ownable_id: String,
 ) -> Result<Response, ContractError> {
+    if BURNED.may_load(deps.storage)?.is_some() {
+        return Err(ContractError::Burned {});
+    }
+    migrate_config(deps.storage)?;
+    let event_id = external_event_id(&event).ok_or(ContractError::InvalidExternalEventArgs {})?;
+    if PROCESSED_EVENTS.has(deps.storage, &event_id) {
+        return Err(ContractError::DuplicateEvent { val: event_id });
+    }
+    let event_type = event.event_type.clone();
+
     let mut response = Response::new()
-        .add_attribute("method", "register_external_event");
+        .add_attribute("method", "register_external_event")
+        .add_attribute("event_id", event_id.clone());
 
     match event.event_type.as_str() {
         "lock" => {
             try_register_lock(
                 info,
-                deps,
+                env,
+                deps.branch(),
                 event,
             )?;
             response = response.add_attribute("event_type", "lock");
         },
         "consume" => {
-            try_register_consume(
+            let consume_response = try_register_consume(
                 info,
-                deps,
+                env,
+                deps.branch(),
                 event,
                 ownable_id,
             )?;
-            response = response.add_attribute("event_type", "consume");
+            // the acknowledgement is passed back to the consumable
+            response = response
+                .add_attribute("event_type", "consume")
+                .add_events(consume_response.events);
 
         }
         _ => return Err(ContractError::MatchEventError { val: event.event_type }),
     };
 
+    PROCESSED_EVENTS.save(deps.storage, &event_id, &event_type)?;
     Ok(response)
 }
 
 fn try_register_consume(
     _info: MessageInfo,
+    env: Env,
     deps: DepsMut,
     event: ExternalEventMsg,
     ownable_id: String,
 ) -> Result<Response, ContractError> {
+    let consume = ConsumeEvent::from_external_event(&event)?;
+    let consumer = Addr::unchecked(ownable_id.clone());
+    // robots instantiated before the id was stored learn it from the first consume
+    if OWNABLE_ID.may_load(deps.storage)?.is_none() {
+        OWNABLE_ID.save(deps.storage, &ownable_id)?;
+    }
 
-    let owner = event.attributes.get("owner")
-        .cloned()
-        .unwrap_or_default();
-    let consumed_by = event.attributes.get("consumed_by")
-        .cloned()
-        .unwrap_or_default();
-    let issuer = event.attributes.get("issuer")
-        .cloned()
-        .unwrap_or_default();
-    let color = event.attributes.get("color")
-        .cloned()
-        .unwrap_or_default();
-    let consumable_type = event.attributes.get("consumable_type")
-        .cloned()
-        .unwrap_or_default();
+    match apply_consume(env, deps, &consume, ownable_id) {
+        Ok(()) => Ok(Response::new()
+            .add_attribute("method", "try_register_consume")
+            .add_attribute("status", "success")
+            .add_event(ConsumeAckEvent {
+                version: PROTOCOL_VERSION,
+                ownable_id: consume.ownable_id,
+                consumer,
+            }.to_event())
+        ),
+        Err(ContractError::Std(err)) => Err(ContractError::Std(err)),
+        // a rejected consumable returns to available instead of being lost
+        Err(err) => Ok(Response::new()
+            .add_attribute("method", "try_register_consume")
+            .add_attribute("status", "rejected")
+            .add_event(ConsumeRejectEvent {
+                version: PROTOCOL_VERSION,
+                ownable_id: consume.ownable_id,
+                consumer,
+                reason: err.to_string(),
+            }.to_event())
+        ),
+    }
+}
 
-    if consumable_type == "paint" {
-        if color.is_empty() {
-            return Err(ContractError::InvalidExternalEventArgs {});
-        }
+fn apply_consume(
+    env: Env,
+    deps: DepsMut,
+    consume: &ConsumeEvent,
+    ownable_id: String,
+) -> Result<(), ContractError> {
+    // the consumable rolls back once the consume expires, so it can't be attached anymore
+    if consume.is_expired(env.block.time.seconds()) {
+        return Err(ContractError::CustomError {
+            val: "Consume event expired".to_string(),
+        });
     }
-    if consumable_type.is_empty() || issuer.is_empty() || consumed_by.is_empty() || owner.is_empty() {
+
+    // the consumable must have been consumed by this robot
+    if consume.consumed_by != ownable_id {
         return Err(ContractError::InvalidExternalEventArgs {});
     }
 
-    let ownership = OWNABLE_INFO.load(deps.storage)?;
-
-    // validate issuer of collection matches
-    if ownership.issuer.to_string() != issuer {
-        return Err(ContractError::InvalidExternalEventArgs {})
+    // validate issuer of collection is allowed by the consume policy
+    if !is_allowed_issuer(deps.storage, &consume.consumable_type, &consume.issuer)? {
+        return Err(ContractError::Unauthorized {
+            val: format!("Consumables of issuer {} are not allowed", consume.issuer),
+        });
     }
 
-    let config_option = CONFIG.load(deps.storage)?;
+    let config_option = load_config(deps.storage)?;
     if let Some(mut config) = config_option {
-        match consumable_type.as_str() {
-            "antenna" => {
-                config.has_antenna = true;
-            },
-            "armor" => {
-                config.has_armor = true;
-            },
-            "paint" => {
-                config.color = color;
-            },
-            "speakers" => {
-                config.has_speaker = true;
-            },
-            _ => {},
+        if config.color_types.contains(&consume.consumable_type) {
+            // a color isn't attached, it changes the color of the robot
+            let paint = match &consume.color {
+                None => return Err(ContractError::InvalidExternalEventArgs {}),
+                Some(color) => color.clone(),
+            };
+            let blend = consume.blend.clone().unwrap_or_default();
+            config.color = blend_color(&config.color, &paint, &blend)?;
+            config.color_history.push(ColorChange {
+                color: config.color.clone(),
+                paint,
+                blend,
+                ownable_id: consume.ownable_id.clone(),
+            });
+        } else {
+            let slot = config.slots.iter_mut()
+                .find(|slot| slot.accepts.contains(&consume.consumable_type) && slot.equipped.is_none());
+            match slot {
+                Some(slot) => {
+                    slot.equipped = Some(Equipment {
+                        ownable_id: consume.ownable_id.clone(),
+                        consumable_type: consume.consumable_type.clone(),
+                        issuer: consume.issuer.clone(),
+                    });
+                },
+                None => return Err(ContractError::CustomError {
+                    val: format!("No free slot for {}", consume.consumable_type),
+                }),
+            }
+        }
+        if let Some(consumable_id) = &consume.ownable_id {
+            config.consumed_ownable_ids.push(Addr::unchecked(consumable_id));
         }
-        config.consumed_ownable_ids.push(Addr::unchecked(ownable_id));
         CONFIG.save(deps.storage, &Some(config))?;
     }
 
-    Ok(Response::new()
-        .add_attribute("method", "try_register_consume")
-        .add_attribute("status", "success")
-    )
+    let owner = OWNABLE_INFO.load(deps.storage)?.owner;
+    record_history(deps.storage, env.block.height, "consume", Some(owner.clone()), owner)?;
+
+    Ok(())
 }
 
-fn try_register_lock(
+pub fn try_set_issuer_allowed(
     info: MessageInfo,
     deps: DepsMut,
-    event: ExternalEventMsg,
+    consumable_type: String,
+    issuer: Addr,
+    allowed: bool,
 ) -> Result<Response, ContractError> {
-    let owner = event.attributes.get("owner")
-        .cloned()
-        .unwrap_or_default();
-    let nft_id = event.attributes.get("token_id")
-        .cloned()
-        .unwrap_or_default();
-    let contract_addr = event.attributes.get("contract")
-        .cloned()
-        .unwrap_or_default();
-
-    if owner.is_empty() || nft_id.is_empty() || contract_addr.is_empty() {
-        return Err(ContractError::InvalidExternalEventArgs {});
-    }
-
-    let nft = NFT_ITEM.load(deps.storage).unwrap();
-    if nft.id.to_string() != nft_id {
-        return Err(ContractError::LockError {
-            val: "nft_id mismatch".to_string()
-        });
-    } else if nft.address != contract_addr {
-        return Err(ContractError::LockError {
-            val: "locking contract mismatch".to_string()
+    let ownership = OWNABLE_INFO.load(deps.storage)?;
+    if info.sender != ownership.issuer {
+        return Err(ContractError::Unauthorized {
+            val: "Only the issuer can change the consume policy".to_string(),
         });
     }
-
-    let event_network = event.network.unwrap_or("".to_string());
-    if event_network == "" {
-        return Err(ContractError::MatchChainIdError { val: "No network".to_string() })
-    } else if event_network != nft.network {
-        return Err(ContractError::LockError {
-            val: "network mismatch".to_string()
+    if consumable_type.is_empty() {
+        return Err(ContractError::CustomError {
+            val: "No consumable type".to_string(),
         });
     }
 
-    let caip_2_fields: Vec<&str> = event_network.split(":").collect();
-    let namespace = caip_2_fields.get(0).unwrap();
-
-    match *namespace {
-        "eip155" => {
-            let address = info.sender.clone();
-            if address.to_string() != owner {
-                return Err(ContractError::Unauthorized {
-                    val: "Only the owner can release an ownable".to_string(),
-                });
-            }
+    let mut issuers = CONSUME_POLICY.may_load(deps.storage, consumable_type.as_str())?
+        .unwrap_or_default();
+    issuers.retain(|i| *i != issuer);
+    if allowed {
+        issuers.push(issuer.clone());
+    }
 
-            Ok(try_release(info, deps, address)?)
-        }
-        _ => return Err(ContractError::MatchChainIdError { val: event_network }),
+    if issuers.is_empty() {
+        CONSUME_POLICY.remove(deps.storage, consumable_type.as_str());
+    } else {
+        CONSUME_POLICY.save(deps.storage, consumable_type.as_str(), &issuers)?;
     }
+
+    Ok(Response::new()
+        .add_attribute("method", "try_set_issuer_allowed")
+        .add_attribute("consumable_type", consumable_type)
+        .add_attribute("issuer", issuer.to_string())
+        .add_attribute("allowed", allowed.to_string())
+    )
 }
 
-pub fn try_lock(info: MessageInfo, deps: DepsMut) -> Result<Response, ContractError> {
-    // only ownable owner can lock it
+pub fn try_detach(
+    info: MessageInfo,
+    deps: DepsMut,
+    slot: String,
+) -> Result<Response, ContractError> {
+    let is_locked = LOCKED.load(deps.storage)?;
+    if is_locked {
+        return Err(ContractError::LockError {
+            val: "Unable to detach add-ons from a locked ownable".to_string(),
+        });
+    }
     let ownership = OWNABLE_INFO.load(deps.storage)?;
-    if info.sender.to_string() != ownership.owner {
+    if !sender_matches(deps.storage, &info.sender, &ownership.owner)? {
         return Err(ContractError::Unauthorized {
-            val: "Unauthorized".into(),
+            val: "Unauthorized detach attempt".into(),
         });
     }
+    let mut config = match load_config(deps.storage)? {
+        None => return Err(ContractError::CustomError { val: "No config found".to_string() }),
+        Some(c) => c,
+    };
 
-    let is_locked = LOCKED.update(
-        deps.storage,
-        |mut is_locked| -> Result<_, ContractError> {
-            if is_locked {
-                return Err(
-                    ContractError::LockError { val: "Already locked".to_string() }
-                );
-            }
-            is_locked = true;
-            Ok(is_locked)
-        }
-    )?;
-
-    Ok(Response::new()
-        .add_attribute("method", "try_lock")
-        .add_attribute("is_locked", is_locked.to_string())
-    )
-}
+    let equipped = match config.slots.iter_mut().find(|s| s.name == slot) {
+        None => return Err(ContractError::CustomError {
+            val: format!("Unknown slot: {}", slot),
+        }),
+        Some(s) => &mut s.equipped,
+    };
+    let equipment = match equipped.take() {
+        None => return Err(ContractError::CustomError {
+            val: format!("Nothing attached to slot {}", slot),
+        }),
+        Some(e) => e,
+    };
 
-fn try_release(_info: MessageInfo, deps: DepsMut, to: Addr) -> Result<Response, ContractError> {
-    let mut is_locked = LOCKED.load(deps.storage)?;
-    if !is_locked {
-        return Err(ContractError::LockError { val: "Not locked".to_string() });
-    }
+    // add-ons consumed before detaching was supported can't be restored
+    let consumable_id = match equipment.ownable_id {
+        None => return Err(ContractError::CustomError {
+            val: format!("The add-on in slot {} can't be detached", slot),
+        }),
+        Some(id) => id,
+    };
 
-    // transfer ownership and unlock
-    let mut ownership = OWNABLE_INFO.load(deps.storage)?;
-    ownership.owner = to;
-    is_locked = false;
+    config.consumed_ownable_ids.retain(|id| *id != consumable_id);
+    CONFIG.save(deps.storage, &Some(config))?;
 
-    OWNABLE_INFO.save(deps.storage, &ownership)?;
-    LOCKED.save(deps.storage, &is_locked)?;
+    let event = RestoreEvent {
+        version: PROTOCOL_VERSION,
+        ownable_id: consumable_id,
+        consumer: Addr::unchecked(OWNABLE_ID.load(deps.storage)?),
+        issuer: equipment.issuer,
+        owner: ownership.owner,
+        consumable_type: equipment.consumable_type,
+    }.to_event();
 
     Ok(Response::new()
-        .add_attribute("method", "try_release")
-        .add_attribute("is_locked", is_locked.to_string())
-        .add_attribute("owner", ownership.owner.to_string())
+        .add_attribute("method", "try_detach")
+        .add_attribute("slot", slot)
+        .add_attribute("external_event", true.to_string())
+        .add_event(event)
     )
 }
 
-pub fn try_transfer(info: MessageInfo, deps: DepsMut, to: Addr) -> Result<Response, ContractError> {
+pub fn try_transfer(info: MessageInfo, env: Env, deps: DepsMut, to: Addr) -> Result<Response, ContractError> {
     let is_locked = LOCKED.load(deps.storage)?;
     if is_locked {
         return Err(ContractError::LockError {
             val: "Unable to transfer a locked ownable".to_string(),
         });
     }
+    if active_loan(deps.storage, env.block.time.seconds())?.is_some() {
+        return Err(ContractError::CustomError {
+            val: "Unable to transfer a lent ownable".to_string(),
+        });
+    }
+    let to = validate_address(to.as_str(), NETWORK_ID.load(deps.storage)?)?;
+    check_transfer_policy(deps.as_ref(), &to, env.block.time.seconds())?;
+    let previous_owner = OWNABLE_INFO.load(deps.storage)?.owner;
+    let approved = is_approved(deps.storage, &info.sender, env.block.time.seconds())?
+        || sender_matches(deps.storage, &info.sender, &previous_owner)?;
     let ownership = OWNABLE_INFO.update(deps.storage, |mut config| -> Result<_, ContractError> {
-        let address = info.sender.clone();
-        if address != config.owner {
+        if !approved {
             return Err(ContractError::Unauthorized {
                 val: "Unauthorized transfer attempt".to_string(),
             });
         }
-        if address == to {
+        if same_address(&to, &config.owner) {
             return Err(ContractError::CustomError {
                 val: "Unable to transfer: Recipient address is current owner".to_string(),
             });
@@ -302,62 +448,449 @@ pub fn try_transfer(info: MessageInfo, deps: DepsMut, to: Addr) -> Result<Respon
         config.owner = to.clone();
         Ok(config)
     })?;
+    clear_approvals(deps.storage)?;
+    TRANSFER_OFFER.remove(deps.storage);
+    APPROVED_RECIPIENT.remove(deps.storage);
+    LOAN.remove(deps.storage);
+    LAST_TRANSFER.save(deps.storage, &env.block.time.seconds())?;
+    record_history(deps.storage, env.block.height, "transfer", Some(previous_owner), ownership.owner.clone())?;
+
     Ok(Response::new()
         .add_attribute("method", "try_transfer")
         .add_attribute("new_owner", ownership.owner.to_string())
     )
 }
 
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
-        QueryMsg::GetInfo {} => query_ownable_info(deps),
+        QueryMsg::GetInfo {} => query_ownable_info(deps, env),
         QueryMsg::GetMetadata {} => query_ownable_metadata(deps),
         QueryMsg::GetWidgetState {} => query_ownable_widget_state(deps),
         QueryMsg::IsLocked {} => query_lock_state(deps),
+        QueryMsg::IsEventProcessed { event_id } => query_event_processed(deps, event_id),
+        QueryMsg::GetHistory { start_after, limit } => query_history(deps, start_after, limit),
+        QueryMsg::GetApprovals {} => query_approvals(deps, env),
+        QueryMsg::GetTokenUri {} => query_token_uri(deps),
         QueryMsg::IsConsumerOf {
             issuer,
             consumable_type
         } => query_is_consumer_of(deps, issuer, consumable_type),
+        QueryMsg::GetConsumePolicy {} => query_consume_policy(deps),
     }
 }
 
 fn query_is_consumer_of(deps: Deps, issuer: Addr, consumable_type: String) -> StdResult<Binary> {
-    let ownable_info = OWNABLE_INFO.load(deps.storage)?;
-
-    let can_consume = match consumable_type.as_str() {
-        "antenna" => true,
-        "armor" => true,
-        "paint" => true,
-        "speakers" => true,
-        _ => false,
-
+    let can_consume = match load_config(deps.storage)? {
+        None => false,
+        Some(config) => config.color_types.contains(&consumable_type) || config.slots.iter()
+            .any(|slot| slot.accepts.contains(&consumable_type)),
     };
-    let same_issuer = ownable_info.issuer == issuer;
-    to_json_binary(&(can_consume && same_issuer))
+    let allowed_issuer = is_allowed_issuer(deps.storage, &consumable_type, &issuer)?;
+    to_json_binary(&(can_consume && allowed_issuer))
+}
+
+fn query_consume_policy(deps: Deps) -> StdResult<Binary> {
+    let ownable_info = OWNABLE_INFO.load(deps.storage)?;
+    let allowed_issuers = CONSUME_POLICY
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(consumable_type, issuers)| AllowedIssuers {
+            consumable_type,
+            issuers,
+        }))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    to_json_binary(&ConsumePolicyResponse {
+        issuer: ownable_info.issuer,
+        allowed_issuers,
+    })
 }
 
 fn query_ownable_widget_state(deps: Deps) -> StdResult<Binary> {
-    let widget_config = CONFIG.load(deps.storage)?;
+    let widget_config = load_config(deps.storage)?;
     to_json_binary(&widget_config)
 }
 
-fn query_lock_state(deps: Deps) -> StdResult<Binary> {
-    let is_locked = LOCKED.load(deps.storage)?;
-    to_json_binary(&is_locked)
+fn query_ownable_metadata(deps: Deps) -> StdResult<Binary> {
+    to_json_binary(&load_metadata_response(deps)?)
 }
 
-fn query_ownable_info(deps: Deps) -> StdResult<Binary> {
-    let nft = NFT_ITEM.may_load(deps.storage)?;
-    let ownable_info = OWNABLE_INFO.load(deps.storage)?;
-    to_json_binary(&InfoResponse {
-        owner: ownable_info.owner,
-        issuer: ownable_info.issuer,
-        nft,
-        ownable_type: ownable_info.ownable_type,
+fn query_token_uri(deps: Deps) -> StdResult<Binary> {
+    let metadata = load_metadata_response(deps)?;
+    to_json_binary(&TokenUriResponse {
+        nft: NFT_ITEM.may_load(deps.storage)?,
+        token_uri: token_uri(&metadata)?,
     })
 }
 
-fn query_ownable_metadata(deps: Deps) -> StdResult<Binary> {
-    let meta = METADATA.load(deps.storage)?;
-    to_json_binary(&meta)
+fn load_metadata_response(deps: Deps) -> StdResult<MetadataResponse> {
+    let mut metadata = METADATA.load(deps.storage)?;
+    let config = load_config(deps.storage)?;
+    let attributes = config.as_ref()
+        .map(metadata_attributes)
+        .unwrap_or_default();
+    // the image is drawn from the current state, unless the issuer supplied image data
+    if let (None, Some(config)) = (&metadata.image_data, &config) {
+        metadata.image_data = Some(render_svg(config));
+    }
+    Ok(MetadataResponse { metadata, attributes })
+}
+
+fn metadata_attributes(config: &Config) -> Vec<MetadataAttribute> {
+    let mut attributes = vec![MetadataAttribute::new("color", &config.color)];
+    for slot in &config.slots {
+        attributes.push(MetadataAttribute::new(
+            &format!("has_{}", slot.name),
+            slot.equipped.is_some(),
+        ));
+    }
+    attributes
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage};
+    use cosmwasm_std::{from_json, OwnedDeps};
+    use ownable_common::event::BlendMode;
+    use super::*;
+
+    const ISSUER: &str = "3NBd71MErsjwmStnj8PQECHP1JL2jvuY2HW";
+    const BOB: &str = "3NAigR72mUpT3pqxvpB1YK6L6Eyn5zyRAz5";
+    const ROBOT_ID: &str = "robot-1";
+
+    fn setup() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+        setup_with(None, None)
+    }
+
+    fn setup_with(slots: Option<Vec<SlotConfig>>, metadata: Option<Metadata>) -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            ownable_id: ROBOT_ID.to_string(),
+            package: "package-cid".to_string(),
+            nft: None,
+            ownable_type: None,
+            network_id: b'T',
+            metadata,
+            slots,
+            color_types: None,
+            immutable_metadata: None,
+            transfer_policy: None,
+            lock_recovery_delay: None,
+            nft_standard: None,
+        };
+        instantiate(deps.as_mut(), mock_env(), mock_info(ISSUER, &[]), msg).unwrap();
+        deps
+    }
+
+    fn consume_event(tx_hash: Option<&str>) -> ExternalEventMsg {
+        let mut attributes = HashMap::from([
+            ("version".to_string(), PROTOCOL_VERSION.to_string()),
+            ("ownable_id".to_string(), "antenna-1".to_string()),
+            ("issuer".to_string(), ISSUER.to_string()),
+            ("owner".to_string(), ISSUER.to_string()),
+            ("consumed_by".to_string(), ROBOT_ID.to_string()),
+            ("consumable_type".to_string(), "antenna".to_string()),
+        ]);
+        if let Some(tx_hash) = tx_hash {
+            attributes.insert("tx_hash".to_string(), tx_hash.to_string());
+            attributes.insert("log_index".to_string(), "3".to_string());
+        }
+        ExternalEventMsg {
+            network: None,
+            event_type: "consume".to_string(),
+            attributes,
+        }
+    }
+
+    #[test]
+    fn test_consume_replay_rejected() {
+        let mut deps = setup();
+        let info = mock_info(ISSUER, &[]);
+
+        let res = register_external_event(info.clone(), mock_env(), deps.as_mut(), consume_event(Some("0xabc")), ROBOT_ID.to_string()).unwrap();
+        assert_eq!(res.events[0].ty, "consume_ack");
+
+        let err = register_external_event(info.clone(), mock_env(), deps.as_mut(), consume_event(Some("0xabc")), ROBOT_ID.to_string()).unwrap_err();
+        assert!(matches!(err, ContractError::DuplicateEvent { .. }));
+
+        // the consumable is only attached once
+        let config = load_config(&deps.storage).unwrap().unwrap();
+        assert_eq!(config.consumed_ownable_ids.len(), 1);
+
+        let processed: bool = from_json(query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::IsEventProcessed { event_id: "0xabc:3".to_string() },
+        ).unwrap()).unwrap();
+        assert!(processed);
+    }
+
+    #[test]
+    fn test_expired_consume_rejected() {
+        let mut deps = setup();
+        let now = mock_env().block.time.seconds();
+        let mut event = consume_event(Some("0xabc"));
+        event.attributes.insert("expires".to_string(), now.to_string());
+
+        // the consumable already rolled back, so the add-on isn't attached
+        let res = register_external_event(mock_info(ISSUER, &[]), mock_env(), deps.as_mut(), event, ROBOT_ID.to_string()).unwrap();
+        assert_eq!(res.events[0].ty, "consume_reject");
+        let config = load_config(&deps.storage).unwrap().unwrap();
+        assert!(config.consumed_ownable_ids.is_empty());
+        assert!(config.slots.iter().all(|slot| slot.equipped.is_none()));
+
+        let mut event = consume_event(Some("0xdef"));
+        event.attributes.insert("expires".to_string(), (now + 1).to_string());
+        let res = register_external_event(mock_info(ISSUER, &[]), mock_env(), deps.as_mut(), event, ROBOT_ID.to_string()).unwrap();
+        assert_eq!(res.events[0].ty, "consume_ack");
+    }
+
+    #[test]
+    fn test_event_without_source_rejected() {
+        let mut deps = setup();
+
+        let err = register_external_event(mock_info(ISSUER, &[]), mock_env(), deps.as_mut(), consume_event(None), ROBOT_ID.to_string()).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidExternalEventArgs {}));
+    }
+
+    #[test]
+    fn test_detach_binds_restore_to_robot() {
+        let mut deps = setup();
+        let info = mock_info(ISSUER, &[]);
+        register_external_event(info.clone(), mock_env(), deps.as_mut(), consume_event(Some("0xabc")), ROBOT_ID.to_string()).unwrap();
+
+        let res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Detach { slot: "antenna".to_string() }).unwrap();
+        let restore = &res.events[0];
+        assert_eq!(restore.ty, "restore");
+        assert!(restore.attributes.iter().any(|attr| attr.key == "consumer" && attr.value == ROBOT_ID));
+    }
+
+    fn image_data(deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>) -> Option<String> {
+        let res: MetadataResponse = from_json(query(deps.as_ref(), mock_env(), QueryMsg::GetMetadata {}).unwrap()).unwrap();
+        res.metadata.image_data
+    }
+
+    #[test]
+    fn test_render_configured_slots() {
+        let slots = vec![SlotConfig { name: "head".to_string(), accepts: vec!["antenna".to_string()] }];
+        let mut deps = setup_with(Some(slots), None);
+        assert!(!image_data(&deps).unwrap().contains(r#"cy="12""#));
+
+        register_external_event(mock_info(ISSUER, &[]), mock_env(), deps.as_mut(), consume_event(Some("0xabc")), ROBOT_ID.to_string()).unwrap();
+        assert!(image_data(&deps).unwrap().contains(r#"cy="12""#));
+    }
+
+    fn attributes(deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>) -> Vec<MetadataAttribute> {
+        let res: MetadataResponse = from_json(query(deps.as_ref(), mock_env(), QueryMsg::GetMetadata {}).unwrap()).unwrap();
+        res.attributes
+    }
+
+    #[test]
+    fn test_metadata_attributes() {
+        let mut deps = setup();
+        assert_eq!(attributes(&deps), vec![
+            MetadataAttribute::new("color", "#195272"),
+            MetadataAttribute::new("has_antenna", false),
+            MetadataAttribute::new("has_armor", false),
+            MetadataAttribute::new("has_speakers", false),
+        ]);
+
+        register_external_event(mock_info(ISSUER, &[]), mock_env(), deps.as_mut(), consume_event(Some("0xabc")), ROBOT_ID.to_string()).unwrap();
+        assert!(attributes(&deps).contains(&MetadataAttribute::new("has_antenna", true)));
+    }
+
+    #[test]
+    fn test_metadata_attributes_of_configured_slots() {
+        let slots = vec![SlotConfig { name: "head".to_string(), accepts: vec!["antenna".to_string()] }];
+        let deps = setup_with(Some(slots), None);
+        let traits: Vec<String> = attributes(&deps).into_iter().map(|attr| attr.trait_type).collect();
+        assert_eq!(traits, vec!["color".to_string(), "has_head".to_string()]);
+    }
+
+    // the metadata document encoded in the token uri
+    fn token_uri_document(deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>) -> MetadataResponse {
+        let res: TokenUriResponse = from_json(query(deps.as_ref(), mock_env(), QueryMsg::GetTokenUri {}).unwrap()).unwrap();
+        assert_eq!(res.nft, None);
+        let encoded = res.token_uri.strip_prefix("data:application/json;base64,").unwrap();
+        from_json(Binary::from_base64(encoded).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_default_token_uri() {
+        let deps = setup();
+        let document = token_uri_document(&deps);
+        assert_eq!(document.metadata.name, Some("Robot".to_string()));
+        assert!(document.metadata.image_data.unwrap().starts_with("<svg"));
+        assert_eq!(document.attributes, attributes(&deps));
+    }
+
+    #[test]
+    fn test_overridden_token_uri() {
+        let metadata = Metadata {
+            name: Some("Rusty".to_string()),
+            image_data: Some("<svg></svg>".to_string()),
+            ..Default::default()
+        };
+        let deps = setup_with(None, Some(metadata));
+        let document = token_uri_document(&deps);
+        assert_eq!(document.metadata.name, Some("Rusty".to_string()));
+        assert_eq!(document.metadata.image_data, Some("<svg></svg>".to_string()));
+        assert!(document.metadata.description.is_some());
+    }
+
+    #[test]
+    fn test_issuer_image_data_kept() {
+        let metadata = Metadata { image_data: Some("<svg></svg>".to_string()), ..Default::default() };
+        let deps = setup_with(None, Some(metadata));
+        assert_eq!(image_data(&deps), Some("<svg></svg>".to_string()));
+    }
+
+    #[test]
+    fn test_color_history() {
+        let mut deps = setup();
+        let paint = |tx_hash: &str, color: &str, blend: Option<&str>| {
+            let mut event = consume_event(Some(tx_hash));
+            event.attributes.insert("ownable_id".to_string(), format!("paint-{}", tx_hash));
+            event.attributes.insert("consumable_type".to_string(), "paint".to_string());
+            event.attributes.insert("color".to_string(), color.to_string());
+            if let Some(blend) = blend {
+                event.attributes.insert("blend".to_string(), blend.to_string());
+            }
+            event
+        };
+
+        register_external_event(mock_info(ISSUER, &[]), mock_env(), deps.as_mut(), paint("0xabc", "#ff0000", None), ROBOT_ID.to_string()).unwrap();
+        register_external_event(mock_info(ISSUER, &[]), mock_env(), deps.as_mut(), paint("0xdef", "#0000FF", Some("mix:50")), ROBOT_ID.to_string()).unwrap();
+
+        let config = load_config(&deps.storage).unwrap().unwrap();
+        assert_eq!(config.color, "#800080");
+        assert_eq!(config.color_history, vec![
+            ColorChange {
+                color: "#FF0000".to_string(),
+                paint: "#ff0000".to_string(),
+                blend: BlendMode::Replace,
+                ownable_id: Some("paint-0xabc".to_string()),
+            },
+            ColorChange {
+                color: "#800080".to_string(),
+                paint: "#0000FF".to_string(),
+                blend: BlendMode::Mix { ratio: 50 },
+                ownable_id: Some("paint-0xdef".to_string()),
+            },
+        ]);
+    }
+
+    fn consume_policy(deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>) -> ConsumePolicyResponse {
+        from_json(query(deps.as_ref(), mock_env(), QueryMsg::GetConsumePolicy {}).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_foreign_issuer_rejected() {
+        let mut deps = setup();
+        let mut event = consume_event(Some("0xabc"));
+        event.attributes.insert("issuer".to_string(), BOB.to_string());
+
+        let res = register_external_event(mock_info(ISSUER, &[]), mock_env(), deps.as_mut(), event, ROBOT_ID.to_string()).unwrap();
+        assert_eq!(res.events[0].ty, "consume_reject");
+        let config = load_config(&deps.storage).unwrap().unwrap();
+        assert!(config.slots.iter().all(|slot| slot.equipped.is_none()));
+
+        let query_msg = QueryMsg::IsConsumerOf { issuer: Addr::unchecked(BOB), consumable_type: "antenna".to_string() };
+        assert!(!from_json::<bool>(query(deps.as_ref(), mock_env(), query_msg).unwrap()).unwrap());
+    }
+
+    #[test]
+    fn test_allow_issuer() {
+        let mut deps = setup();
+        let allow = ExecuteMsg::AllowIssuer { consumable_type: "antenna".to_string(), issuer: Addr::unchecked(BOB) };
+        let err = execute(deps.as_mut(), mock_env(), mock_info(BOB, &[]), allow.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized { .. }));
+        execute(deps.as_mut(), mock_env(), mock_info(ISSUER, &[]), allow.clone()).unwrap();
+        // allowing twice doesn't list the issuer twice
+        execute(deps.as_mut(), mock_env(), mock_info(ISSUER, &[]), allow).unwrap();
+
+        assert_eq!(consume_policy(&deps), ConsumePolicyResponse {
+            issuer: Addr::unchecked(ISSUER),
+            allowed_issuers: vec![AllowedIssuers {
+                consumable_type: "antenna".to_string(),
+                issuers: vec![Addr::unchecked(BOB)],
+            }],
+        });
+
+        // only for the allowed consumable type
+        let query_msg = QueryMsg::IsConsumerOf { issuer: Addr::unchecked(BOB), consumable_type: "armor".to_string() };
+        assert!(!from_json::<bool>(query(deps.as_ref(), mock_env(), query_msg).unwrap()).unwrap());
+
+        let mut event = consume_event(Some("0xabc"));
+        event.attributes.insert("issuer".to_string(), BOB.to_string());
+        let res = register_external_event(mock_info(ISSUER, &[]), mock_env(), deps.as_mut(), event, ROBOT_ID.to_string()).unwrap();
+        assert_eq!(res.events[0].ty, "consume_ack");
+        let config = load_config(&deps.storage).unwrap().unwrap();
+        let antenna = config.slots.iter().find(|slot| slot.name == "antenna").unwrap();
+        assert_eq!(antenna.equipped.as_ref().unwrap().issuer, BOB);
+    }
+
+    #[test]
+    fn test_disallow_issuer() {
+        let mut deps = setup();
+        let allow = ExecuteMsg::AllowIssuer { consumable_type: "antenna".to_string(), issuer: Addr::unchecked(BOB) };
+        execute(deps.as_mut(), mock_env(), mock_info(ISSUER, &[]), allow).unwrap();
+
+        let disallow = ExecuteMsg::DisallowIssuer { consumable_type: "antenna".to_string(), issuer: Addr::unchecked(BOB) };
+        execute(deps.as_mut(), mock_env(), mock_info(BOB, &[]), disallow.clone()).unwrap_err();
+        execute(deps.as_mut(), mock_env(), mock_info(ISSUER, &[]), disallow).unwrap();
+        assert!(consume_policy(&deps).allowed_issuers.is_empty());
+
+        let mut event = consume_event(Some("0xabc"));
+        event.attributes.insert("issuer".to_string(), BOB.to_string());
+        let res = register_external_event(mock_info(ISSUER, &[]), mock_env(), deps.as_mut(), event, ROBOT_ID.to_string()).unwrap();
+        assert_eq!(res.events[0].ty, "consume_reject");
+
+        // the robot's own issuer is always allowed
+        let disallow = ExecuteMsg::DisallowIssuer { consumable_type: "antenna".to_string(), issuer: Addr::unchecked(ISSUER) };
+        execute(deps.as_mut(), mock_env(), mock_info(ISSUER, &[]), disallow).unwrap();
+        let res = register_external_event(mock_info(ISSUER, &[]), mock_env(), deps.as_mut(), consume_event(Some("0xdef")), ROBOT_ID.to_string()).unwrap();
+        assert_eq!(res.events[0].ty, "consume_ack");
+    }
+
+    #[test]
+    fn test_build_slots() {
+        let slot = |name: &str, accepts: &[&str]| SlotConfig {
+            name: name.to_string(),
+            accepts: accepts.iter().map(|t| t.to_string()).collect(),
+        };
+
+        let slots = build_slots(vec![slot("head", &["antenna"]), slot("body", &["armor", "speakers"])]).unwrap();
+        assert_eq!(slots.len(), 2);
+        assert_eq!(slots[1].accepts, vec!["armor".to_string(), "speakers".to_string()]);
+        assert!(slots.iter().all(|slot| slot.equipped.is_none()));
+
+        build_slots(vec![slot("", &["antenna"])]).unwrap_err();
+        build_slots(vec![slot("head", &[])]).unwrap_err();
+        build_slots(vec![slot("head", &["antenna"]), slot("head", &["armor"])]).unwrap_err();
+    }
+
+    #[test]
+    fn test_consume_color_type() {
+        let mut deps = setup();
+        let mut event = consume_event(Some("0xabc"));
+        event.attributes.insert("consumable_type".to_string(), "paint".to_string());
+
+        // a paint without a color is refused
+        let res = register_external_event(mock_info(ISSUER, &[]), mock_env(), deps.as_mut(), event.clone(), ROBOT_ID.to_string()).unwrap();
+        assert_eq!(res.events[0].ty, "consume_reject");
+
+        event.attributes.insert("color".to_string(), "#FF0000".to_string());
+        event.attributes.insert("tx_hash".to_string(), "0xdef".to_string());
+        register_external_event(mock_info(ISSUER, &[]), mock_env(), deps.as_mut(), event, ROBOT_ID.to_string()).unwrap();
+
+        let config = load_config(&deps.storage).unwrap().unwrap();
+        assert_eq!(config.color, "#FF0000");
+        assert!(config.slots.iter().all(|slot| slot.equipped.is_none()));
+
+        let query_msg = QueryMsg::IsConsumerOf { issuer: Addr::unchecked(ISSUER), consumable_type: "paint".to_string() };
+        assert!(from_json::<bool>(query(deps.as_ref(), mock_env(), query_msg).unwrap()).unwrap());
+        let query_msg = QueryMsg::IsConsumerOf { issuer: Addr::unchecked(ISSUER), consumable_type: "potion".to_string() };
+        assert!(!from_json::<bool>(query(deps.as_ref(), mock_env(), query_msg).unwrap()).unwrap());
+    }
 }
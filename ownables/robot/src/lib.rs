@@ -3,15 +3,16 @@ extern crate core;
 use std::str;
 
 use contract::instantiate;
-use cosmwasm_std::{MessageInfo};
-use ownable_std::{create_env, ExternalEventMsg, get_json_response, IdbStateDump, load_owned_deps};
+use cosmwasm_std::{Env, MessageInfo, Timestamp};
+use ownable_std::{create_ownable_env, ExternalEventMsg, get_json_response, IdbStateDump, load_owned_deps};
 use msg::{ExecuteMsg, InstantiateMsg};
 use serde_json::{to_string};
 use wasm_bindgen::prelude::*;
 
+pub mod color;
 pub mod contract;
-pub mod error;
 pub mod msg;
+pub mod render;
 pub mod state;
 
 #[wasm_bindgen]
@@ -22,17 +23,26 @@ extern "C" {
     pub fn log(s: &str);
 }
 
+// every event of the event chain is a block of the ownable, the wallet passes its position and its time in milliseconds
+fn create_env_at(time: f64, event_index: u32) -> Env {
+    let mut env = create_ownable_env(String::new(), Some(Timestamp::from_nanos(time as u64 * 1_000_000)));
+    env.block.height = event_index as u64;
+    env
+}
+
 #[wasm_bindgen]
 pub async fn instantiate_contract(
     msg: JsValue,
     info: JsValue,
+    time: f64,
+    event_index: u32,
 ) -> Result<JsValue, JsError> {
 
     let msg: InstantiateMsg = serde_wasm_bindgen::from_value(msg)?;
     let info: MessageInfo = serde_wasm_bindgen::from_value(info)?;
     let mut deps = load_owned_deps(None);
 
-    let res = instantiate(deps.as_mut(), create_env(), info, msg);
+    let res = instantiate(deps.as_mut(), create_env_at(time, event_index), info, msg);
 
     match res {
         Ok(response) => {
@@ -48,6 +58,8 @@ pub async fn execute_contract(
     msg: JsValue,
     info: JsValue,
     idb: JsValue,
+    time: f64,
+    event_index: u32,
 ) -> Result<JsValue, JsError> {
     let message: ExecuteMsg = serde_wasm_bindgen::from_value(msg.clone())?;
     let info: MessageInfo = serde_wasm_bindgen::from_value(info)?;
@@ -56,7 +68,7 @@ pub async fn execute_contract(
 
     let result = contract::execute(
         deps.as_mut(),
-        create_env(),
+        create_env_at(time, event_index),
         info,
         message
     );
@@ -76,6 +88,8 @@ pub async fn register_external_event(
     info: JsValue,
     ownable_id: String,
     idb: JsValue,
+    time: f64,
+    event_index: u32,
 ) -> Result<JsValue, JsError> {
     log(&format!("msg: {:?}", msg));
     let external_event: ExternalEventMsg = serde_wasm_bindgen::from_value(msg.clone())?;
@@ -85,6 +99,7 @@ pub async fn register_external_event(
 
     let result = contract::register_external_event(
         info,
+        create_env_at(time, event_index),
         deps.as_mut(),
         external_event,
         ownable_id,
@@ -103,13 +118,14 @@ pub async fn register_external_event(
 pub async fn query_contract_state(
     msg: JsValue,
     idb: JsValue,
+    time: f64,
 ) -> Result<JsValue, JsError> {
     let state_dump: IdbStateDump = serde_wasm_bindgen::from_value(idb)?;
     let deps = load_owned_deps(Some(state_dump));
 
     let query_result = contract::query(
         deps.as_ref(),
-        create_env(),
+        create_env_at(time, 0),
         serde_wasm_bindgen::from_value(msg)?
     );
 
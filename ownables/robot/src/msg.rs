@@ -1,5 +1,7 @@
-use cosmwasm_std::{Addr};
-use ownable_std::NFT;
+use cosmwasm_std::Addr;
+use ownable_std::{Metadata, NFT};
+use ownable_common::msg::MetadataUpdate;
+use ownable_common::state::{NftStandard, TransferPolicy};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use ownable_std_macros::{
@@ -12,13 +14,63 @@ use ownable_std_macros::{
 #[ownables_instantiate_msg]
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
-pub struct InstantiateMsg {}
+pub struct InstantiateMsg {
+    // merged over the default metadata of the ownable
+    pub metadata: Option<Metadata>,
+    // add-on slots of the robot, defaults to antenna, armor and speakers
+    pub slots: Option<Vec<SlotConfig>>,
+    // consumable types that change the color instead of being attached, defaults to paint
+    pub color_types: Option<Vec<String>>,
+    // metadata fields the issuer can't change after instantiate
+    pub immutable_metadata: Option<Vec<String>>,
+    // defaults to freely transferable
+    pub transfer_policy: Option<TransferPolicy>,
+    // seconds after locking before the issuer can recover the ownable, defaults to 30 days
+    pub lock_recovery_delay: Option<u64>,
+    // defaults to the standard of the network of the NFT, ERC-721 for eip155
+    pub nft_standard: Option<NftStandard>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct SlotConfig {
+    pub name: String,
+    // consumable types that can be attached to the slot
+    pub accepts: Vec<String>,
+}
 
 #[ownables_transfer]
 #[ownables_lock]
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
-pub enum ExecuteMsg {}
+pub enum ExecuteMsg {
+    // detach the add-on in the given slot, so it can be restored as a consumable
+    Detach { slot: String },
+    // allow consumables of another issuer, only the issuer of the robot can change the policy
+    AllowIssuer { consumable_type: String, issuer: Addr },
+    DisallowIssuer { consumable_type: String, issuer: Addr },
+    // only the issuer can update or freeze the metadata
+    UpdateMetadata { metadata: MetadataUpdate },
+    FreezeMetadata {},
+    // approved spenders and operators can transfer on behalf of the owner
+    Approve { spender: Addr, expires: Option<u64> },
+    Revoke { spender: Addr },
+    ApproveAll { operator: Addr, expires: Option<u64> },
+    RevokeAll { operator: Addr },
+    // two-step transfer, ownership changes when the recipient accepts the offer
+    OfferTransfer { to: Addr, expires: Option<u64> },
+    AcceptTransfer {},
+    CancelOffer {},
+    // only the issuer can approve the recipient if the transfer policy requires it
+    ApproveTransfer { to: Addr },
+    // the borrower gets the user rights until the given block time in seconds
+    Lend { to: Addr, until: u64 },
+    Reclaim {},
+    // permanently destroys the ownable, only a tombstone is left
+    Burn {},
+    // unlocks an ownable stuck in the locked state, issuer only
+    RecoverLock {},
+}
 
 #[ownables_query_info]
 #[ownables_query_locked]
@@ -27,4 +79,29 @@ pub enum ExecuteMsg {}
 #[ownables_query_widget_state]
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
-pub enum QueryMsg {}
+pub enum QueryMsg {
+    GetConsumePolicy {},
+    // ERC-721 metadata of the ownable for the NFT it's linked to
+    GetTokenUri {},
+    GetApprovals {},
+    // provenance log, oldest entries first
+    GetHistory { start_after: Option<u64>, limit: Option<u32> },
+    // true if the external event with this id is applied
+    IsEventProcessed { event_id: String },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct ConsumePolicyResponse {
+    // consumables of the robot's own issuer are always allowed
+    pub issuer: Addr,
+    pub allowed_issuers: Vec<AllowedIssuers>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct AllowedIssuers {
+    pub consumable_type: String,
+    pub issuers: Vec<Addr>,
+}
+
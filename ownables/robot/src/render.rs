@@ -0,0 +1,79 @@
+use crate::color::{desaturate, tint};
+use crate::state::Config;
+
+const FALLBACK_COLOR: &str = "#000000";
+
+/// Render the robot as SVG, matching the drawing of the widget
+pub fn render_svg(config: &Config) -> String {
+    let main = safe_color(&config.color);
+    let face = tint(main, 85).unwrap_or_else(|_| main.to_string());
+    let body = desaturate(&face, 40).unwrap_or_else(|_| face.clone());
+
+    let mut svg = String::from(r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 200 200">"#);
+
+    // body and head
+    svg.push_str(&format!(
+        r#"<circle cx="100" cy="115" r="80" fill="{}" stroke="{}" stroke-width="5"/>"#,
+        body, main,
+    ));
+    svg.push_str(&format!(
+        r##"<rect x="38" y="30" width="124" height="114" rx="40" fill="#ffffff" stroke="{}" stroke-width="5"/>"##,
+        main,
+    ));
+
+    // face
+    svg.push_str(&format!(
+        r#"<rect x="52" y="60" width="96" height="70" rx="30" fill="{}" stroke="{}" stroke-width="5"/>"#,
+        face, main,
+    ));
+    svg.push_str(&format!(r#"<circle cx="80" cy="90" r="6" fill="{}"/>"#, main));
+    svg.push_str(&format!(r#"<circle cx="120" cy="90" r="6" fill="{}"/>"#, main));
+    svg.push_str(&format!(
+        r#"<path d="M 90 110 C 90 110, 100 125, 110 110" fill="none" stroke="{}" stroke-width="5"/>"#,
+        main,
+    ));
+
+    // add-ons
+    if has_add_on(config, "antenna") {
+        svg.push_str(&format!(
+            r#"<line x1="100" y1="15" x2="100" y2="30" stroke="{}" stroke-width="5"/>"#,
+            main,
+        ));
+        svg.push_str(&format!(
+            r##"<circle cx="100" cy="12" r="8" fill="#ffffff" stroke="{}" stroke-width="5"/>"##,
+            main,
+        ));
+    }
+    if has_add_on(config, "speakers") {
+        for d in ["M 38 85 V 60 C 38 60, 5 85, 38 110 V 85", "M 162 85 V 60 C 162 60, 195 85, 162 110 V 85"] {
+            svg.push_str(&format!(
+                r##"<path d="{}" fill="#ffffff" stroke="{}" stroke-width="5"/>"##,
+                d, main,
+            ));
+        }
+    }
+    if has_add_on(config, "armor") {
+        svg.push_str(&format!(
+            r##"<path d="M 100 165 H 60 V 184 C 60 184, 100 208, 140 184 V 165 H 100" fill="#ffffff" stroke="{}" stroke-width="5"/>"##,
+            main,
+        ));
+    }
+
+    svg.push_str("</svg>");
+    svg
+}
+
+// add-ons are drawn by their type, whatever slot of the configured slots holds them
+fn has_add_on(config: &Config, consumable_type: &str) -> bool {
+    config.slots.iter()
+        .filter_map(|slot| slot.equipped.as_ref())
+        .any(|equipment| equipment.consumable_type == consumable_type)
+}
+
+// only hex colors are written into the svg, anything else could inject markup
+fn safe_color(color: &str) -> &str {
+    let valid = color.len() == 7
+        && color.starts_with('#')
+        && color[1..].chars().all(|c| c.is_ascii_hexdigit());
+    if valid { color } else { FALLBACK_COLOR }
+}
@@ -1,23 +1,184 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use cosmwasm_std::Addr;
-use cw_storage_plus::Item;
-use ownable_std::{Metadata, NFT, OwnableInfo};
+use cosmwasm_std::{Addr, StdResult, Storage};
+use cw_storage_plus::{Item, Map};
+use ownable_common::event::BlendMode;
+use ownable_common::state::OWNABLE_INFO;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Config {
     // None if still available to consumption
     pub consumed_ownable_ids: Vec<Addr>,
     pub color: String,
-    pub has_antenna: bool,
-    pub has_speaker: bool,
-    pub has_armor: bool,
+    pub slots: Vec<Slot>,
+    // consumable types that change the color of the robot instead of being attached
+    #[serde(default = "default_color_types")]
+    pub color_types: Vec<String>,
+    #[serde(default)]
+    pub color_history: Vec<ColorChange>,
+}
+
+/// Color of the robot after consuming a paint
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ColorChange {
+    pub color: String,
+    pub paint: String,
+    pub blend: BlendMode,
+    // None for paints instantiated before their id was stored
+    pub ownable_id: Option<String>,
+}
+
+/// Named add-on slot and the consumable types it accepts
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Slot {
+    pub name: String,
+    pub accepts: Vec<String>,
+    // None if nothing is attached to the slot
+    pub equipped: Option<Equipment>,
+}
+
+/// Consumable currently attached to one of the robot's add-on slots
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Equipment {
+    // None for add-ons consumed before their id was part of the consume event
+    pub ownable_id: Option<String>,
+    pub consumable_type: String,
+    pub issuer: Addr,
+}
+
+/// Config as stored before add-ons were modelled as slots
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+struct LegacyConfig {
+    consumed_ownable_ids: Vec<Addr>,
+    color: String,
+    has_antenna: bool,
+    has_speaker: bool,
+    has_armor: bool,
 }
 
 pub const CONFIG: Item<Option<Config>> = Item::new("config");
-pub const OWNABLE_INFO: Item<OwnableInfo> = Item::new("ownable_info");
-pub const METADATA: Item<Metadata> = Item::new("metadata");
-pub const NFT_ITEM: Item<NFT> = Item::new("nft");
-pub const LOCKED: Item<bool> = Item::new("is_locked");
-pub const PACKAGE_CID: Item<String> = Item::new("package_cid");
-pub const NETWORK_ID: Item<u8> = Item::new("network_id");
+// issuers other than the robot's own issuer allowed per consumable type
+pub const CONSUME_POLICY: Map<&str, Vec<Addr>> = Map::new("consume_policy");
+
+const LEGACY_CONFIG: Item<Option<LegacyConfig>> = Item::new("config");
+
+/// Slots of a robot that wasn't configured with its own slots at instantiate
+pub fn default_slots() -> Vec<Slot> {
+    ["antenna", "armor", "speakers"].iter()
+        .map(|name| Slot {
+            name: name.to_string(),
+            accepts: vec![name.to_string()],
+            equipped: None,
+        })
+        .collect()
+}
+
+/// Consumable types that change the color of a robot that wasn't configured with its own
+pub fn default_color_types() -> Vec<String> {
+    vec!["paint".to_string()]
+}
+
+/// Load the config, converting a config with hardcoded add-on flags to the slot model.
+/// The converted config is only stored by `migrate_config`.
+pub fn load_config(storage: &dyn Storage) -> StdResult<Option<Config>> {
+    let err = match CONFIG.load(storage) {
+        Ok(config) => return Ok(config),
+        Err(err) => err,
+    };
+    let legacy = match LEGACY_CONFIG.load(storage) {
+        Ok(Some(legacy)) => legacy,
+        Ok(None) => return Ok(None),
+        Err(_) => return Err(err),
+    };
+    let issuer = OWNABLE_INFO.load(storage)?.issuer;
+
+    let mut slots = default_slots();
+    for slot in slots.iter_mut() {
+        let is_attached = match slot.name.as_str() {
+            "antenna" => legacy.has_antenna,
+            "armor" => legacy.has_armor,
+            "speakers" => legacy.has_speaker,
+            _ => false,
+        };
+        if is_attached {
+            slot.equipped = Some(Equipment {
+                ownable_id: None,
+                consumable_type: slot.name.clone(),
+                issuer: issuer.clone(),
+            });
+        }
+    }
+
+    Ok(Some(Config {
+        consumed_ownable_ids: legacy.consumed_ownable_ids,
+        color: legacy.color,
+        slots,
+        color_types: default_color_types(),
+        color_history: vec![],
+    }))
+}
+
+/// Store a config with hardcoded add-on flags in the slot model
+pub fn migrate_config(storage: &mut dyn Storage) -> StdResult<()> {
+    if CONFIG.load(storage).is_ok() {
+        return Ok(());
+    }
+    let config = load_config(storage)?;
+    CONFIG.save(storage, &config)
+}
+
+/// Whether consumables of the given type and issuer may be consumed by this robot
+pub fn is_allowed_issuer(
+    storage: &dyn Storage,
+    consumable_type: &str,
+    issuer: &Addr,
+) -> StdResult<bool> {
+    let ownership = OWNABLE_INFO.load(storage)?;
+    if ownership.issuer == *issuer {
+        return Ok(true);
+    }
+    let allowed = CONSUME_POLICY.may_load(storage, consumable_type)?.unwrap_or_default();
+    Ok(allowed.contains(issuer))
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::testing::MockStorage;
+    use ownable_std::OwnableInfo;
+    use super::*;
+
+    const ISSUER: &str = "3NBd71MErsjwmStnj8PQECHP1JL2jvuY2HW";
+
+    #[test]
+    fn test_migrate_legacy_config() {
+        let mut storage = MockStorage::new();
+        OWNABLE_INFO.save(&mut storage, &OwnableInfo {
+            owner: Addr::unchecked(ISSUER),
+            issuer: Addr::unchecked(ISSUER),
+            ownable_type: Some("robot".to_string()),
+        }).unwrap();
+        LEGACY_CONFIG.save(&mut storage, &Some(LegacyConfig {
+            consumed_ownable_ids: vec![Addr::unchecked("antenna-1")],
+            color: "#ff0000".to_string(),
+            has_antenna: true,
+            has_speaker: false,
+            has_armor: false,
+        })).unwrap();
+
+        migrate_config(&mut storage).unwrap();
+
+        let config = CONFIG.load(&storage).unwrap().unwrap();
+        let antenna = config.slots.iter().find(|slot| slot.name == "antenna").unwrap();
+        assert_eq!(antenna.equipped, Some(Equipment {
+            ownable_id: None,
+            consumable_type: "antenna".to_string(),
+            issuer: Addr::unchecked(ISSUER),
+        }));
+        assert!(config.slots.iter().filter(|slot| slot.name != "antenna").all(|slot| slot.equipped.is_none()));
+        assert_eq!(config.color_types, default_color_types());
+
+        // a migrated config is left as is
+        migrate_config(&mut storage).unwrap();
+        assert_eq!(CONFIG.load(&storage).unwrap().unwrap(), config);
+    }
+}
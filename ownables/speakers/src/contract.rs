@@ -1,12 +1,20 @@
 use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
-use crate::state::{NFT_ITEM, Config, CONFIG, METADATA, LOCKED, PACKAGE_CID, OWNABLE_INFO, NETWORK_ID};
-use cosmwasm_std::{to_json_binary, Binary, Attribute, Event};
+use ownable_common::msg::{MetadataResponse, MetadataAttribute, TokenUriResponse};
+use crate::state::{Config, PendingConsume, CONFIG};
+use ownable_common::state::{NFT_ITEM, METADATA, LOCKED, PACKAGE_CID, OWNABLE_INFO, NETWORK_ID, OWNABLE_ID, MetadataLock, METADATA_LOCK, clear_approvals, is_approved, record_history, TRANSFER_OFFER, has_pending_offer, TRANSFER_POLICY, APPROVED_RECIPIENT, LAST_TRANSFER, LOAN, active_loan, BURNED, LOCK_RECOVERY_DELAY, DEFAULT_LOCK_RECOVERY_DELAY, PROCESSED_EVENTS, external_event_id, NftStandard, NFT_STANDARD};
+use cosmwasm_std::{to_json_binary, Binary};
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::{Addr, Deps, DepsMut, Env, MessageInfo, Response, StdResult};
 use cw2::set_contract_version;
-use ownable_std::{ExternalEventMsg, InfoResponse, Metadata, OwnableInfo};
-use crate::error::ContractError;
-
+use ownable_std::{ExternalEventMsg, Metadata, OwnableInfo};
+use ownable_common::error::ContractError;
+use ownable_common::execute::{try_register_lock, try_lock, try_update_metadata, try_freeze_metadata, try_approve, try_revoke, try_approve_all, try_revoke_all, try_offer_transfer, try_accept_transfer, try_cancel_offer, try_approve_transfer, check_transfer_policy, try_lend, try_reclaim, try_burn, try_recover_lock};
+use ownable_common::query::{query_event_processed, query_lock_state, query_ownable_info, query_approvals, query_history};
+use ownable_common::nft::AssetId;
+use ownable_common::identity::sender_matches;
+use ownable_common::address::{same_address, validate_address};
+use ownable_common::metadata::{merge_metadata, validate_immutable_fields, validate_metadata, token_uri};
+use ownable_common::event::{ConsumeAckEvent, ConsumeEvent, ConsumeRejectEvent, ExternalEvent, CONSUME_TIMEOUT, RestoreEvent, PROTOCOL_VERSION};
 
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:ownable-speakers";
@@ -14,7 +22,7 @@ const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 pub fn instantiate(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
@@ -25,7 +33,7 @@ pub fn instantiate(
         ownable_type: Some("speakers".to_string()),
     };
 
-    let metadata = Metadata {
+    let metadata = merge_metadata(Metadata {
         image: None,
         image_data: None,
         external_url: None,
@@ -34,22 +42,42 @@ pub fn instantiate(
         background_color: None,
         animation_url: None,
         youtube_url: None,
+    }, msg.metadata.clone());
+    validate_metadata(&metadata)?;
+    let metadata_lock = MetadataLock {
+        frozen: false,
+        immutable_fields: msg.immutable_metadata.clone().unwrap_or_default(),
     };
+    validate_immutable_fields(&metadata_lock.immutable_fields)?;
 
     let config = Config {
         consumed_by: None,
+        pending: None,
         color: "".to_string(),
     };
 
     NETWORK_ID.save(deps.storage, &msg.network_id)?;
     CONFIG.save(deps.storage, &Some(config.clone()))?;
     if let Some(nft) = msg.nft {
+        // links without an explicit standard predate CAIP-19 and aren't validated
+        if let Some(standard) = &msg.nft_standard {
+            AssetId::from_nft(&nft, standard)?.validate()?;
+        }
+        let standard = msg.nft_standard.clone()
+            .unwrap_or_else(|| NftStandard::for_network(&nft.network));
         NFT_ITEM.save(deps.storage, &nft)?;
+        NFT_STANDARD.save(deps.storage, &standard)?;
     }
     METADATA.save(deps.storage, &metadata)?;
+    METADATA_LOCK.save(deps.storage, &metadata_lock)?;
+    TRANSFER_POLICY.save(deps.storage, &msg.transfer_policy.clone().unwrap_or_default())?;
+    LOCK_RECOVERY_DELAY.save(deps.storage, &msg.lock_recovery_delay.unwrap_or(DEFAULT_LOCK_RECOVERY_DELAY))?;
+    LAST_TRANSFER.save(deps.storage, &env.block.time.seconds())?;
     LOCKED.save(deps.storage, &false)?;
     OWNABLE_INFO.save(deps.storage, &ownable_info)?;
+    record_history(deps.storage, env.block.height, "instantiate", None, ownable_info.owner.clone())?;
     PACKAGE_CID.save(deps.storage, &msg.package)?;
+    OWNABLE_ID.save(deps.storage, &msg.ownable_id)?;
 
     Ok(Response::new()
         .add_attribute("method", "instantiate")
@@ -61,150 +89,224 @@ pub fn instantiate(
 
 pub fn execute(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
+    if BURNED.may_load(deps.storage)?.is_some() {
+        return Err(ContractError::Burned {});
+    }
     match msg {
-        ExecuteMsg::Consume {} => try_consume(info, deps),
-        ExecuteMsg::Transfer { to } => try_transfer(info, deps, to),
-        ExecuteMsg::Lock {} => try_lock(info, deps),
+        ExecuteMsg::Consume { consumer } => try_consume(info, env, deps, consumer),
+        ExecuteMsg::Transfer { to } => try_transfer(info, env, deps, to),
+        ExecuteMsg::Lock {} => try_lock(info, env, deps),
+        ExecuteMsg::RecoverLock {} => try_recover_lock(info, env, deps),
+        ExecuteMsg::Burn {} => try_burn(info, env, deps),
+        ExecuteMsg::Lend { to, until } => try_lend(info, env, deps, to, until),
+        ExecuteMsg::Reclaim {} => try_reclaim(info, env, deps),
+        ExecuteMsg::ApproveTransfer { to } => try_approve_transfer(info, deps, to),
+        ExecuteMsg::OfferTransfer { to, expires } => try_offer_transfer(info, env, deps, to, expires),
+        ExecuteMsg::AcceptTransfer {} => try_accept_transfer(info, env, deps, try_transfer),
+        ExecuteMsg::CancelOffer {} => try_cancel_offer(info, deps),
+        ExecuteMsg::Approve { spender, expires } => try_approve(info, env, deps, spender, expires),
+        ExecuteMsg::Revoke { spender } => try_revoke(info, env, deps, spender),
+        ExecuteMsg::ApproveAll { operator, expires } => try_approve_all(info, env, deps, operator, expires),
+        ExecuteMsg::RevokeAll { operator } => try_revoke_all(info, deps, operator),
+        ExecuteMsg::UpdateMetadata { metadata } => try_update_metadata(info, deps, metadata),
+        ExecuteMsg::FreezeMetadata {} => try_freeze_metadata(info, deps),
     }
 }
 
 pub fn register_external_event(
     info: MessageInfo,
-    deps: DepsMut,
+    env: Env,
+    mut deps: DepsMut,
     event: ExternalEventMsg,
-    _ownable_id: String,
+    ownable_id: String,
 ) -> Result<Response, ContractError> {
+    if BURNED.may_load(deps.storage)?.is_some() {
+        return Err(ContractError::Burned {});
+    }
+    let event_id = external_event_id(&event).ok_or(ContractError::InvalidExternalEventArgs {})?;
+    if PROCESSED_EVENTS.has(deps.storage, &event_id) {
+        return Err(ContractError::DuplicateEvent { val: event_id });
+    }
+    let event_type = event.event_type.clone();
+
     let mut response = Response::new()
-        .add_attribute("method", "register_external_event");
+        .add_attribute("method", "register_external_event")
+        .add_attribute("event_id", event_id.clone());
 
     match event.event_type.as_str() {
         "lock" => {
             try_register_lock(
                 info,
-                deps,
+                env,
+                deps.branch(),
                 event,
             )?;
             response = response.add_attribute("event_type", "lock");
         },
+        "consume_ack" => {
+            try_register_consume_ack(
+                info,
+                env,
+                deps.branch(),
+                event,
+                ownable_id,
+            )?;
+            response = response.add_attribute("event_type", "consume_ack");
+        },
+        "consume_reject" => {
+            try_register_consume_reject(
+                info,
+                env,
+                deps.branch(),
+                event,
+                ownable_id,
+            )?;
+            response = response.add_attribute("event_type", "consume_reject");
+        },
+        "restore" => {
+            try_register_restore(
+                info,
+                env,
+                deps.branch(),
+                event,
+                ownable_id,
+            )?;
+            response = response.add_attribute("event_type", "restore");
+        },
         _ => return Err(ContractError::MatchEventError { val: event.event_type }),
     };
 
+    PROCESSED_EVENTS.save(deps.storage, &event_id, &event_type)?;
     Ok(response)
 }
 
-fn try_register_lock(
+fn try_register_restore(
     info: MessageInfo,
+    env: Env,
     deps: DepsMut,
     event: ExternalEventMsg,
+    ownable_id: String,
 ) -> Result<Response, ContractError> {
-    let owner = event.attributes.get("owner")
-        .cloned()
-        .unwrap_or_default();
-    let nft_id = event.attributes.get("token_id")
-        .cloned()
-        .unwrap_or_default();
-    let contract_addr = event.attributes.get("contract")
-        .cloned()
-        .unwrap_or_default();
-
-    if owner.is_empty() || nft_id.is_empty() || contract_addr.is_empty() {
+    let restore = RestoreEvent::from_external_event(&event)?;
+    if restore.ownable_id != ownable_id {
         return Err(ContractError::InvalidExternalEventArgs {});
     }
 
-    let nft = NFT_ITEM.load(deps.storage).unwrap();
-    if nft.id.to_string() != nft_id {
-        return Err(ContractError::LockError {
-            val: "nft_id mismatch".to_string()
-        });
-    } else if nft.address != contract_addr {
-        return Err(ContractError::LockError {
-            val: "locking contract mismatch".to_string()
+    // the consumer may have changed hands, the ownable goes to the owner of the consumer
+    let owner = validate_address(restore.owner.as_str(), NETWORK_ID.load(deps.storage)?)?;
+    if !sender_matches(deps.storage, &info.sender, &owner)? {
+        return Err(ContractError::Unauthorized {
+            val: "Only the owner of the consumer can restore an ownable".to_string(),
         });
     }
+    let ownership = OWNABLE_INFO.load(deps.storage)?;
+    if ownership.issuer != restore.issuer
+        || ownership.ownable_type.clone().unwrap_or_default() != restore.consumable_type {
+        return Err(ContractError::InvalidExternalEventArgs {});
+    }
 
-    let event_network = event.network.unwrap_or("".to_string());
-    if event_network == "" {
-        return Err(ContractError::MatchChainIdError { val: "No network".to_string() })
-    } else if event_network != nft.network {
-        return Err(ContractError::LockError {
-            val: "network mismatch".to_string()
+    let mut config = match CONFIG.load(deps.storage)? {
+        None => return Err(ContractError::CustomError { val: "No config found".to_string() }),
+        Some(c) => c,
+    };
+    // only the consumer that absorbed the ownable can release it
+    if config.consumed_by.as_ref() != Some(&restore.consumer) {
+        return Err(ContractError::CustomError {
+            val: format!("not consumed by {}", restore.consumer),
         });
     }
+    config.consumed_by = None;
 
-    let caip_2_fields: Vec<&str> = event_network.split(":").collect();
-    let namespace = caip_2_fields.get(0).unwrap();
-
-    match *namespace {
-        "eip155" => {
-            let address = info.sender.clone();
-            if address.to_string() != owner {
-                return Err(ContractError::Unauthorized {
-                    val: "Only the owner can release an ownable".to_string(),
-                });
-            }
-
-            Ok(try_release(info, deps, address)?)
-        }
-        _ => return Err(ContractError::MatchChainIdError { val: event_network }),
+    CONFIG.save(deps.storage, &Some(config))?;
+    if !same_address(&owner, &ownership.owner) {
+        OWNABLE_INFO.save(deps.storage, &OwnableInfo { owner: owner.clone(), ..ownership.clone() })?;
+        clear_approvals(deps.storage)?;
+        LAST_TRANSFER.save(deps.storage, &env.block.time.seconds())?;
     }
+    record_history(deps.storage, env.block.height, "restore", Some(ownership.owner), owner.clone())?;
+
+    Ok(Response::new()
+        .add_attribute("method", "try_register_restore")
+        .add_attribute("owner", owner.to_string())
+    )
 }
 
-pub fn try_lock(info: MessageInfo, deps: DepsMut) -> Result<Response, ContractError> {
-    // only ownable owner can lock it
-    let ownership = OWNABLE_INFO.load(deps.storage)?;
-    if info.sender.to_string() != ownership.owner {
-        return Err(ContractError::Unauthorized {
-            val: "Unauthorized".into(),
-        });
-    }
+fn try_register_consume_ack(
+    _info: MessageInfo,
+    env: Env,
+    deps: DepsMut,
+    event: ExternalEventMsg,
+    ownable_id: String,
+) -> Result<Response, ContractError> {
+    let ack = ConsumeAckEvent::from_external_event(&event)?;
+    let mut config = load_pending_config(deps.as_ref(), &env, ack.ownable_id, &ack.consumer, ownable_id)?;
 
-    let is_locked = LOCKED.update(
-        deps.storage,
-        |mut is_locked| -> Result<_, ContractError> {
-            if is_locked {
-                return Err(
-                    ContractError::LockError { val: "Already locked".to_string() }
-                );
-            }
-            is_locked = true;
-            Ok(is_locked)
-        }
-    )?;
+    config.consumed_by = Some(ack.consumer);
+    config.pending = None;
+    CONFIG.save(deps.storage, &Some(config.clone()))?;
+
+    let owner = OWNABLE_INFO.load(deps.storage)?.owner;
+    record_history(deps.storage, env.block.height, "consume", Some(owner.clone()), owner)?;
 
     Ok(Response::new()
-        .add_attribute("method", "try_lock")
-        .add_attribute("is_locked", is_locked.to_string())
+        .add_attribute("method", "try_register_consume_ack")
+        .add_attribute("consumed_by", config.consumed_by.unwrap().to_string())
     )
 }
 
-fn try_release(_info: MessageInfo, deps: DepsMut, to: Addr) -> Result<Response, ContractError> {
-    let mut is_locked = LOCKED.load(deps.storage)?;
-    if !is_locked {
-        return Err(ContractError::LockError { val: "Not locked".to_string() });
-    }
-
-    // transfer ownership and unlock
-    let mut ownership = OWNABLE_INFO.load(deps.storage)?;
-    ownership.owner = to;
-    is_locked = false;
+fn try_register_consume_reject(
+    _info: MessageInfo,
+    env: Env,
+    deps: DepsMut,
+    event: ExternalEventMsg,
+    ownable_id: String,
+) -> Result<Response, ContractError> {
+    let reject = ConsumeRejectEvent::from_external_event(&event)?;
+    let mut config = load_pending_config(deps.as_ref(), &env, reject.ownable_id, &reject.consumer, ownable_id)?;
 
-    OWNABLE_INFO.save(deps.storage, &ownership)?;
-    LOCKED.save(deps.storage, &is_locked)?;
+    config.pending = None;
+    CONFIG.save(deps.storage, &Some(config))?;
 
     Ok(Response::new()
-        .add_attribute("method", "try_release")
-        .add_attribute("is_locked", is_locked.to_string())
-        .add_attribute("owner", ownership.owner.to_string())
+        .add_attribute("method", "try_register_consume_reject")
+        .add_attribute("reason", reject.reason)
     )
 }
 
+// config of a consumable waiting for the given consumer to respond
+fn load_pending_config(
+    deps: Deps,
+    env: &Env,
+    event_ownable_id: Option<String>,
+    consumer: &Addr,
+    ownable_id: String,
+) -> Result<Config, ContractError> {
+    if event_ownable_id.is_some_and(|id| id != ownable_id) {
+        return Err(ContractError::InvalidExternalEventArgs {});
+    }
+
+    let config = match CONFIG.load(deps.storage)? {
+        None => return Err(ContractError::CustomError { val: "No config found".to_string() }),
+        Some(c) => c,
+    };
+    // a consume that timed out is rolled back, so a late response is refused
+    match &config.pending {
+        Some(pending) if pending.consumer == *consumer && config.is_pending(env.block.time.seconds(), CONSUME_TIMEOUT) => Ok(config),
+        _ => Err(ContractError::CustomError {
+            val: "No pending consume for this consumer".to_string(),
+        }),
+    }
+}
+
 pub fn try_consume(
     info: MessageInfo,
+    env: Env,
     deps: DepsMut,
+    consumer: String,
 ) -> Result<Response, ContractError> {
     let is_locked = LOCKED.load(deps.storage)?;
     if is_locked {
@@ -214,7 +316,7 @@ pub fn try_consume(
     }
     let ownership = OWNABLE_INFO.load(deps.storage)?;
     let config = CONFIG.load(deps.storage)?;
-    if info.sender.to_string() != ownership.owner {
+    if !sender_matches(deps.storage, &info.sender, &ownership.owner)? {
         return Err(ContractError::Unauthorized {
             val: "Unauthorized consumption attempt".into(),
         });
@@ -224,56 +326,107 @@ pub fn try_consume(
         Some(c) => c,
     };
 
-    if let Some(_) = config.consumed_by {
+    if config.consumed_by.is_some() {
         return Err(ContractError::CustomError {
             val: "already consumed".into(),
         });
     }
-    config.consumed_by = Some(ownership.clone().owner);
+    if config.is_pending(env.block.time.seconds(), CONSUME_TIMEOUT) {
+        return Err(ContractError::CustomError {
+            val: "consume pending".into(),
+        });
+    }
+    if has_pending_offer(deps.storage, env.block.time.seconds())? {
+        return Err(ContractError::CustomError {
+            val: "transfer offer pending".into(),
+        });
+    }
+    if active_loan(deps.storage, env.block.time.seconds())?.is_some() {
+        return Err(ContractError::CustomError {
+            val: "ownable is lent out".into(),
+        });
+    }
+    let consumable_type = match ownership.ownable_type.clone() {
+        None => return Err(ContractError::CustomError { val: "No ownable type found".to_string() }),
+        Some(t) => t,
+    };
+
+    // ownables instantiated before the id was stored can't be restored after detaching
+    let ownable_id = OWNABLE_ID.may_load(deps.storage)?;
+    if consumer.is_empty() || Some(&consumer) == ownable_id.as_ref() {
+        return Err(ContractError::CustomError {
+            val: "Invalid consumer".into(),
+        });
+    }
+
+    // only marked as consumed once the consumer acknowledges the event
+    let consumer = Addr::unchecked(consumer);
+    let since = env.block.time.seconds();
+    config.pending = Some(PendingConsume {
+        consumer: consumer.clone(),
+        since,
+    });
     CONFIG.save(deps.storage, &Some(config.clone()))?;
 
-    let mut event = Event::new("consume".to_string());
-    event = event.add_attributes(vec![
-        Attribute {
-            key: "issuer".to_string(),
-            value: ownership.issuer.to_string(),
-        },
-        Attribute {
-            key: "owner".to_string(),
-            value: ownership.owner.to_string()
-        },
-        Attribute {
-            key: "consumed_by".to_string(),
-            value: config.consumed_by.unwrap().to_string(),
-        },
-        Attribute {
-            key: "consumable_type".to_string(),
-            value: ownership.ownable_type.unwrap_or("armor".to_string()),
-        },
-    ]);
+    let event = ConsumeEvent {
+        version: PROTOCOL_VERSION,
+        ownable_id,
+        issuer: ownership.issuer,
+        owner: ownership.owner,
+        consumed_by: consumer.clone(),
+        consumable_type,
+        color: None,
+        blend: None,
+        expires: Some(since + CONSUME_TIMEOUT),
+    }.to_event();
 
     Ok(Response::new()
         .add_attribute("method", "try_consume")
+        .add_attribute("consumer", consumer.to_string())
         .add_attribute("external_event", true.to_string())
         .add_event(event)
     )
 }
 
-pub fn try_transfer(info: MessageInfo, deps: DepsMut, to: Addr) -> Result<Response, ContractError> {
+pub fn try_transfer(info: MessageInfo, env: Env, deps: DepsMut, to: Addr) -> Result<Response, ContractError> {
     let is_locked = LOCKED.load(deps.storage)?;
     if is_locked {
         return Err(ContractError::LockError {
             val: "Unable to transfer a locked ownable".to_string(),
         });
     }
+    if let Some(mut config) = CONFIG.load(deps.storage)? {
+        if config.consumed_by.is_some() {
+            return Err(ContractError::CustomError {
+                val: "Unable to transfer a consumed ownable".to_string(),
+            });
+        }
+        if config.is_pending(env.block.time.seconds(), CONSUME_TIMEOUT) {
+            return Err(ContractError::CustomError {
+                val: "Unable to transfer an ownable that is being consumed".to_string(),
+            });
+        }
+        // a consume that timed out is rolled back
+        config.pending = None;
+        CONFIG.save(deps.storage, &Some(config))?;
+    }
+    if active_loan(deps.storage, env.block.time.seconds())?.is_some() {
+        return Err(ContractError::CustomError {
+            val: "Unable to transfer a lent ownable".to_string(),
+        });
+    }
+    let to = validate_address(to.as_str(), NETWORK_ID.load(deps.storage)?)?;
+    check_transfer_policy(deps.as_ref(), &to, env.block.time.seconds())?;
+    let previous_owner = OWNABLE_INFO.load(deps.storage)?.owner;
+    let approved = is_approved(deps.storage, &info.sender, env.block.time.seconds())?
+        || sender_matches(deps.storage, &info.sender, &previous_owner)?;
     let ownership = OWNABLE_INFO.update(deps.storage, |mut config| -> Result<_, ContractError> {
-        let address = info.sender.clone();
-        if address != config.owner {
+        if !approved {
             return Err(ContractError::Unauthorized {
                 val: "Unauthorized transfer attempt".to_string(),
             });
         }
-        if address == to {
+        if same_address(&to, &config.owner) {
             return Err(ContractError::CustomError {
                 val: "Unable to transfer: Recipient address is current owner".to_string(),
             });
@@ -281,18 +434,29 @@ pub fn try_transfer(info: MessageInfo, deps: DepsMut, to: Addr) -> Result<Respon
         config.owner = to.clone();
         Ok(config)
     })?;
+    clear_approvals(deps.storage)?;
+    TRANSFER_OFFER.remove(deps.storage);
+    APPROVED_RECIPIENT.remove(deps.storage);
+    LOAN.remove(deps.storage);
+    LAST_TRANSFER.save(deps.storage, &env.block.time.seconds())?;
+    record_history(deps.storage, env.block.height, "transfer", Some(previous_owner), ownership.owner.clone())?;
+
     Ok(Response::new()
         .add_attribute("method", "try_transfer")
         .add_attribute("new_owner", ownership.owner.to_string())
     )
 }
 
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
-        QueryMsg::GetInfo {} => query_ownable_info(deps),
+        QueryMsg::GetInfo {} => query_ownable_info(deps, env),
         QueryMsg::GetMetadata {} => query_ownable_metadata(deps),
         QueryMsg::GetWidgetState {} => query_ownable_widget_state(deps),
         QueryMsg::IsLocked {} => query_lock_state(deps),
+        QueryMsg::IsEventProcessed { event_id } => query_event_processed(deps, event_id),
+        QueryMsg::GetHistory { start_after, limit } => query_history(deps, start_after, limit),
+        QueryMsg::GetApprovals {} => query_approvals(deps, env),
+        QueryMsg::GetTokenUri {} => query_token_uri(deps),
     }
 }
 
@@ -301,23 +465,29 @@ fn query_ownable_widget_state(deps: Deps) -> StdResult<Binary> {
     to_json_binary(&widget_config)
 }
 
-fn query_lock_state(deps: Deps) -> StdResult<Binary> {
-    let is_locked = LOCKED.load(deps.storage)?;
-    to_json_binary(&is_locked)
+fn query_ownable_metadata(deps: Deps) -> StdResult<Binary> {
+    to_json_binary(&load_metadata_response(deps)?)
 }
 
-fn query_ownable_info(deps: Deps) -> StdResult<Binary> {
-    let nft = NFT_ITEM.may_load(deps.storage)?;
-    let ownable_info = OWNABLE_INFO.load(deps.storage)?;
-    to_json_binary(&InfoResponse {
-        owner: ownable_info.owner,
-        issuer: ownable_info.issuer,
-        nft,
-        ownable_type: ownable_info.ownable_type,
+fn query_token_uri(deps: Deps) -> StdResult<Binary> {
+    let metadata = load_metadata_response(deps)?;
+    to_json_binary(&TokenUriResponse {
+        nft: NFT_ITEM.may_load(deps.storage)?,
+        token_uri: token_uri(&metadata)?,
     })
 }
 
-fn query_ownable_metadata(deps: Deps) -> StdResult<Binary> {
-    let meta = METADATA.load(deps.storage)?;
-    to_json_binary(&meta)
+fn load_metadata_response(deps: Deps) -> StdResult<MetadataResponse> {
+    let metadata = METADATA.load(deps.storage)?;
+    let attributes = CONFIG.load(deps.storage)?
+        .map(|config| metadata_attributes(&config))
+        .unwrap_or_default();
+    Ok(MetadataResponse { metadata, attributes })
+}
+
+fn metadata_attributes(config: &Config) -> Vec<MetadataAttribute> {
+    vec![
+        MetadataAttribute::new("color", &config.color),
+        MetadataAttribute::new("consumed", config.consumed_by.is_some()),
+    ]
 }
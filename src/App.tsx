@@ -8,6 +8,7 @@ import Sidebar from "./components/Sidebar";
 import { ViewMessagesBar } from "./components/ViewMessagesBar";
 import If from "./components/If";
 import { HAS_EXAMPLES } from "./services/Package.service";
+import { CosmWasmEvent } from "./services/Ownable.service";
 import Grid from "@mui/material/Unstable_Grid2";
 import * as React from "react";
 import Ownable from "./components/Ownable";
@@ -289,7 +290,13 @@ export default function App() {
 
     ownableService
       .consume(consumer, consumable, onProgress)
-      .then(() => {
+      .then((rejected) => {
+        if (rejected !== undefined) {
+          setOwnables((ownables) => [...ownables]);
+          ctrl.close();
+          showError("Consume rejected", rejected);
+          return;
+        }
         setConsuming(null);
         setOwnables((ownables) => [...ownables]);
         enqueueSnackbar("Consumed", { variant: "success" });
@@ -301,6 +308,60 @@ export default function App() {
       });
   };
 
+  const restore = (consumer: EventChain, event: CosmWasmEvent) => {
+    if (!ownableService) throw new Error("Ownable service not ready");
+
+    const consumable = ownables.find(
+      ({ chain }) => chain.id === event.attributes.ownable_id
+    );
+    if (!consumable) {
+      showError(
+        "Restore failed",
+        "The detached Ownable isn't in this wallet. Import it to use it again."
+      );
+      return;
+    }
+
+    ownableService
+      .restore(consumer, consumable.chain, event)
+      .then(() => {
+        setOwnables((ownables) => [...ownables]);
+        enqueueSnackbar("Restored", { variant: "success" });
+      })
+      .catch((error) => {
+        showError("Restore failed", ownableErrorMessage(error));
+      });
+  };
+
+  const pour = (source: EventChain, event: CosmWasmEvent) => {
+    if (!ownableService) throw new Error("Ownable service not ready");
+
+    const target = ownables.find(
+      ({ chain }) => chain.id === event.attributes.target
+    );
+    if (!target) {
+      showError(
+        "Pour failed",
+        "The target potion isn't in this wallet. The pour is rolled back once it expires."
+      );
+      return;
+    }
+
+    ownableService
+      .pour(source, target.chain, event)
+      .then((rejected) => {
+        setOwnables((ownables) => [...ownables]);
+        if (rejected !== undefined) {
+          showError("Pour rejected", rejected);
+          return;
+        }
+        enqueueSnackbar("Poured", { variant: "success" });
+      })
+      .catch((error) => {
+        showError("Pour failed", ownableErrorMessage(error));
+      });
+  };
+
   const reset = async () => {
     setShowSidebar(false);
     if (ownables.length === 0) return;
@@ -445,6 +506,8 @@ export default function App() {
               onConsume={(info) =>
                 setConsuming({ chain, package: packageCid, info })
               }
+              onRestore={(event) => restore(chain, event)}
+              onPour={(event) => pour(chain, event)}
               onError={showError}
             >
               <If condition={consuming?.chain.id === chain.id}>
@@ -17,28 +17,32 @@ addEventListener('message', (e) => {
     return;
   }
 
+  // the time and event index are passed after the state, packages built before they existed ignore them
+  const time = e.data.time ?? Date.now();
+  const eventIndex = e.data.event_index ?? 0;
+
   switch (e.data.type) {
     case "instantiate":
       e.data.msg.nft = (!e.data.msg.nft) ? undefined : e.data.msg.nft;
       e.data.msg.ownable_type = (!e.data.msg.ownable_type) ? undefined : e.data.msg.ownable_type;
-      e.data.msg.network_id = 0;
-      instantiate_contract(e.data.msg, e.data.info)
+      e.data.msg.network_id = e.data.msg.network_id ?? 0;
+      instantiate_contract(e.data.msg, e.data.info, time, eventIndex)
         .then(resp => self.postMessage(resp))
         .catch(err => self.postMessage({err}));
       break;
     case "execute":
-      execute_contract(e.data.msg, e.data.info, e.data.mem)
+      execute_contract(e.data.msg, e.data.info, e.data.mem, time, eventIndex)
         .then(resp => self.postMessage(resp))
         .catch(err => self.postMessage({err}));
       break;
     case "external_event":
       const messageInfo = e.data.info;
-      register_external_event(e.data.msg.msg, messageInfo.info, e.data.ownable_id, e.data.mem)
+      register_external_event(e.data.msg.msg, messageInfo.info, e.data.ownable_id, e.data.mem, time, eventIndex)
         .then(resp => self.postMessage(resp))
         .catch(err => self.postMessage({err}));
       break;
     case "query":
-      query_contract_state(e.data.msg, e.data.mem)
+      query_contract_state(e.data.msg, e.data.mem, time)
         .then(resp => self.postMessage(resp))
         .catch(err => self.postMessage({err}));
       break;
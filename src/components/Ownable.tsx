@@ -12,7 +12,7 @@ import { Cancelled, connect as rpcConnect } from "simple-iframe-rpc";
 import { Binary, EventChain, IMessageMeta } from "eqty-core";
 import OwnableActions from "./OwnableActions";
 import OwnableInfo from "./OwnableInfo";
-import { OwnableRPC, StateDump } from "../services/Ownable.service";
+import { CosmWasmEvent, OwnableRPC, StateDump } from "../services/Ownable.service";
 import {
   TypedMetadata,
   TypedOwnableInfo,
@@ -36,6 +36,8 @@ interface OwnableProps {
   uniqueMessageHash?: string;
   onDelete: () => void;
   onConsume: (info: TypedOwnableInfo) => void;
+  onRestore: (event: CosmWasmEvent) => void;
+  onPour: (event: CosmWasmEvent) => void;
   onRemove: () => void;
   onError: (title: string, message: string) => void;
   children?: ReactNode;
@@ -212,12 +214,25 @@ export default function Ownable(props: OwnableProps) {
     async (msg: TypedDict, onProgress?: LogProgress, submitAnchors: boolean = true): Promise<void> => {
       if (!ownables) return;
       try {
-        const sd = await ownables.execute(chain, msg, stateDump, onProgress);
+        const { state: sd, events } = await ownables.execute(
+          chain,
+          msg,
+          stateDump,
+          onProgress
+        );
         if (submitAnchors) await ownables.submitAnchors(onProgress);
 
         await refresh(sd);
         setApplied(chain.latestHash);
         setStateDump(sd);
+
+        // Detached consumables and poured potions process the event on their own chain
+        events
+          .filter((event) => event.type === "restore")
+          .forEach((event) => props.onRestore(event));
+        events
+          .filter((event) => event.type === "pour")
+          .forEach((event) => props.onPour(event));
       } catch (e) {
         props.onError("The Ownable returned an error", ownableErrorMessage(e));
       }
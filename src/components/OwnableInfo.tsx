@@ -106,6 +106,19 @@ export default function OwnableInfo(props: OwnableInfoProps) {
         >
           {metadata?.description}
         </DialogTitle>
+        <If condition={!!metadata?.attributes?.length}>
+          <DialogTitle component="div" sx={{ pt: 0, pb: 1.5 }}>
+            {metadata?.attributes?.map(({ trait_type, value }) => (
+              <Chip
+                key={trait_type}
+                label={`${trait_type}: ${value}`}
+                size="small"
+                variant="outlined"
+                sx={{ mr: 1, mb: 1 }}
+              />
+            ))}
+          </DialogTitle>
+        </If>
         <DialogContent>
           <If condition={chain.events.length === 0}>
             <Typography variant="body2" sx={{ color: "text.secondary" }}>
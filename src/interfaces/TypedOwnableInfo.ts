@@ -21,6 +21,12 @@ export interface TypedMetadata {
   background_color?: string;
   animation_url?: string;
   youtube_url?: string;
+  attributes?: TypedMetadataAttribute[];
+}
+
+export interface TypedMetadataAttribute {
+  trait_type: string;
+  value: string;
 }
 
 export interface TypedOwnable {
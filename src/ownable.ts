@@ -71,7 +71,9 @@ function workerCall<T extends Response | string>(
   ownableId: string,
   msg: Dict,
   info: Dict,
-  state?: StateDump
+  state?: StateDump,
+  time: number = Date.now(),
+  eventIndex: number = 0
 ): Promise<{ response: T; state: StateDump }> {
   return new Promise((resolve, reject) => {
     if (!worker) {
@@ -104,6 +106,8 @@ function workerCall<T extends Response | string>(
       ownable_id: ownableId,
       msg,
       info,
+      time,
+      event_index: eventIndex,
       mem: { state_dump: state },
     });
   });
@@ -111,13 +115,18 @@ function workerCall<T extends Response | string>(
 
 async function instantiate(
   msg: Dict,
-  info: Dict
+  info: Dict,
+  time?: number,
+  eventIndex?: number
 ): Promise<{ attributes: Dict; state: StateDump }> {
   const { response, state } = await workerCall<Response>(
     "instantiate",
     ownableId,
     msg,
-    info
+    info,
+    undefined,
+    time,
+    eventIndex
   );
 
   return { attributes: attributesToDict(response.attributes), state };
@@ -126,7 +135,9 @@ async function instantiate(
 async function execute(
   msg: Dict,
   info: MessageInfo,
-  state: StateDump
+  state: StateDump,
+  time?: number,
+  eventIndex?: number
 ): Promise<{
   attributes: Dict;
   events: Array<CosmWasmEvent>;
@@ -138,7 +149,9 @@ async function execute(
     ownableId,
     msg,
     info,
-    state
+    state,
+    time,
+    eventIndex
   );
   return executeResponse(response, newState);
 }
@@ -146,7 +159,9 @@ async function execute(
 async function externalEvent(
   msg: Dict,
   messageInfo: MessageInfo,
-  state: StateDump
+  state: StateDump,
+  time?: number,
+  eventIndex?: number
 ): Promise<{
   attributes: Dict;
   events: Array<CosmWasmEvent>;
@@ -162,7 +177,9 @@ async function externalEvent(
     ownableId,
     msg,
     info,
-    state
+    state,
+    time,
+    eventIndex
   );
   return executeResponse(response, newState);
 }
@@ -20,7 +20,7 @@ interface MessageInfo {
   funds: Array<{}>;
 }
 
-interface CosmWasmEvent {
+export interface CosmWasmEvent {
   type: string;
   attributes: TypedDict<string>;
 }
@@ -29,12 +29,16 @@ export interface OwnableRPC {
   init: (id: string, js: string, wasm: Uint8Array) => Promise<any>;
   instantiate: (
     msg: TypedDict,
-    info: MessageInfo
+    info: MessageInfo,
+    time?: number,
+    eventIndex?: number
   ) => Promise<{ attributes: TypedDict<string>; state: StateDump }>;
   execute: (
     msg: TypedDict,
     info: MessageInfo,
-    state: StateDump
+    state: StateDump,
+    time?: number,
+    eventIndex?: number
   ) => Promise<{
     attributes: TypedDict<string>;
     events: Array<CosmWasmEvent>;
@@ -44,7 +48,9 @@ export interface OwnableRPC {
   externalEvent: (
     msg: TypedDict,
     info: TypedDict,
-    state: StateDump
+    state: StateDump,
+    time?: number,
+    eventIndex?: number
   ) => Promise<{
     attributes: TypedDict<string>;
     events: Array<CosmWasmEvent>;
@@ -284,14 +290,16 @@ export default class OwnableService {
       funds: [],
     } as MessageInfo;
     const { "@context": context, ...msg } = event.parsedData;
+    // Replay the event at the time it was signed, so expiries and cooldowns are deterministic
+    const time = event.timestamp ?? Date.now();
 
     let result;
     switch (context) {
       case "instantiate_msg.json":
-        result = await rpc.instantiate(msg, info);
+        result = await rpc.instantiate(msg, info, time, eventIndex);
         break;
       case "execute_msg.json":
-        result = await rpc.execute(msg, info, stateDump);
+        result = await rpc.execute(msg, info, stateDump, time, eventIndex);
         break;
       case "external_event_msg.json":
         const message = {
@@ -301,7 +309,7 @@ export default class OwnableService {
             network: "",
           },
         };
-        result = await rpc.externalEvent(message, info, stateDump);
+        result = await rpc.externalEvent(message, info, stateDump, time, eventIndex);
         break;
       default:
         throw new Error(`Unknown event type`);
@@ -379,14 +387,25 @@ export default class OwnableService {
     msg: TypedDict,
     stateDump: StateDump,
     onProgress?: LogProgress
-  ): Promise<StateDump> {
+  ): Promise<{ state: StateDump; events: Array<CosmWasmEvent> }> {
     const info = { sender: this.eqty.address, funds: [] } as MessageInfo;
-    const { state: newStateDump } = await this.rpc(chain.id).execute(
+    // The message is signed as the next event on the chain
+    const { state: newStateDump, events } = await this.rpc(chain.id).execute(
       msg,
       info,
-      stateDump
+      stateDump,
+      Date.now(),
+      chain.events.length
     );
 
+    // Restore and pour events refer to the message on this chain, so they can be delivered to the other ownable
+    for (const event of events.filter(
+      (event) => event.type === "restore" || event.type === "pour"
+    )) {
+      event.attributes.tx_hash = chain.latestHash.hex;
+      event.attributes.log_index = String(chain.events.length);
+    }
+
     delete msg["@context"]; // Shouldn't be set
 
     await withProgress(onProgress)("signEvent", () =>
@@ -398,7 +417,58 @@ export default class OwnableService {
     // Store without submitting anchors yet; submission is controlled by caller
     await this.store(chain, newStateDump);
 
-    return newStateDump;
+    return { state: newStateDump, events };
+  }
+
+  /**
+   * Deliver a restore event of a consumer to the consumable it detached, so it can be used again.
+   */
+  async restore(
+    consumer: EventChain,
+    consumable: EventChain,
+    restoreEvent: CosmWasmEvent,
+    onProgress?: LogProgress
+  ): Promise<void> {
+    const info: MessageInfo = {
+      sender: this.eqty.address,
+      funds: [],
+    };
+    const consumableState = await this.eventChains.getStateDump(
+      consumable.id,
+      consumable.state.hex
+    );
+    if (!consumableState) throw Error("State mismatch for restore");
+
+    const restoreEventMsg = {
+      msg: {
+        attributes: restoreEvent.attributes,
+        network: "",
+        event_type: restoreEvent.type,
+      },
+    };
+
+    const { state: restoredStateDump } = await this.rpc(
+      consumable.id
+    ).externalEvent(
+      restoreEventMsg,
+      info,
+      consumableState,
+      Date.now(),
+      consumable.events.length
+    );
+
+    await withProgress(onProgress)("signEvent", () =>
+      this.eqty.sign(
+        new Event({
+          "@context": "external_event_msg.json",
+          contract: consumer.id,
+          ...restoreEvent,
+        }).addTo(consumable)
+      )
+    );
+
+    await this.store(consumable, restoredStateDump);
+    await this.submitAnchors(onProgress);
   }
 
   async submitAnchors(onProgress?: LogProgress): Promise<string | undefined> {
@@ -438,16 +508,19 @@ export default class OwnableService {
     }
   }
 
+  /**
+   * Consume an ownable, resolves with the reason if the consumer rejected it.
+   */
   async consume(
     consumer: EventChain,
     consumable: EventChain,
     onProgress?: LogProgress
-  ): Promise<void> {
+  ): Promise<string | undefined> {
     const info: MessageInfo = {
       sender: this.eqty.address,
       funds: [],
     };
-    const consumeMessage = { consume: {} };
+    const consumeMessage = { consume: { consumer: consumer.id } };
     const consumerState = await this.eventChains.getStateDump(
       consumer.id,
       consumer.state.hex
@@ -459,15 +532,21 @@ export default class OwnableService {
     if (!consumerState || !consumableState)
       throw Error("State mismatch for consume");
 
+    // Both ownables process the consume at the same time, so they agree on whether it expired
+    const time = Date.now();
+
     const { events, state: consumableStateDump } = await this.rpc(
       consumable.id
-    ).execute(consumeMessage, info, consumableState);
+    ).execute(consumeMessage, info, consumableState, time, consumable.events.length);
 
     const consumeEvent:
       | { contract?: string; type: string; attributes: TypedDict<string> }
       | undefined = events.find((event) => event.type === "consume");
     if (!consumeEvent) throw Error("No consume event emitted");
     consumeEvent.contract = consumable.id;
+    // The consume message is the next event on the consumable chain
+    consumeEvent.attributes.tx_hash = consumable.latestHash.hex;
+    consumeEvent.attributes.log_index = String(consumable.events.length);
 
     const externalEventMsg = {
       msg: {
@@ -477,17 +556,74 @@ export default class OwnableService {
       },
     };
 
-    const { state: consumerStateDump } = await this.rpc(
-      consumer.id
-    ).externalEvent(externalEventMsg, info, consumerState);
+    const { events: consumerEvents, state: consumerStateDump } =
+      await this.rpc(consumer.id).externalEvent(
+        externalEventMsg,
+        info,
+        consumerState,
+        time,
+        consumer.events.length
+      );
 
-    await withProgress(onProgress)("signConsumableEvent", () =>
-      this.eqty.sign(
+    // The consumable stays pending until the consumer acknowledges or rejects the event
+    const rejectEvent = consumerEvents.find(
+      (event) => event.type === "consume_reject"
+    );
+    const responseEvent:
+      | { contract?: string; type: string; attributes: TypedDict<string> }
+      | undefined =
+      rejectEvent ??
+      consumerEvents.find((event) => event.type === "consume_ack");
+    if (!responseEvent) throw Error("No consume acknowledgement emitted");
+    responseEvent.contract = consumer.id;
+    if (rejectEvent) {
+      // A rejection isn't added to the consumer chain, it follows the consume message on the consumable chain
+      responseEvent.attributes.tx_hash = consumable.latestHash.hex;
+      responseEvent.attributes.log_index = String(consumable.events.length + 1);
+    } else {
+      // The consume event is the next event on the consumer chain
+      responseEvent.attributes.tx_hash = consumer.latestHash.hex;
+      responseEvent.attributes.log_index = String(consumer.events.length);
+    }
+
+    const responseEventMsg = {
+      msg: {
+        attributes: responseEvent.attributes,
+        network: "",
+        event_type: responseEvent.type,
+      },
+    };
+
+    const { state: consumedStateDump } = await this.rpc(
+      consumable.id
+    ).externalEvent(
+      responseEventMsg,
+      info,
+      consumableStateDump,
+      time,
+      consumable.events.length + 1
+    );
+
+    await withProgress(onProgress)("signConsumableEvent", async () => {
+      await this.eqty.sign(
         new Event({ "@context": "execute_msg.json", ...consumeMessage }).addTo(
           consumable
         )
-      )
-    );
+      );
+      await this.eqty.sign(
+        new Event({
+          "@context": "external_event_msg.json",
+          ...responseEvent,
+        }).addTo(consumable)
+      );
+    });
+
+    // The rejected consumable is available again, the consumer doesn't change
+    if (rejectEvent) {
+      await this.store(consumable, consumedStateDump);
+      await this.submitAnchors(onProgress);
+      return rejectEvent.attributes.reason;
+    }
 
     await withProgress(onProgress)("signConsumerEvent", () =>
       this.eqty.sign(
@@ -500,13 +636,113 @@ export default class OwnableService {
 
     // Store both chains; emit anchor progress only once to represent anchoring both
     // Queue anchors for both chains without submitting yet
-    await this.store(consumable, consumableStateDump);
+    await this.store(consumable, consumedStateDump);
     await this.store(consumer, consumerStateDump);
 
     // Submit a single anchor tx for both
     await this.submitAnchors(onProgress);
   }
 
+  /**
+   * Deliver a pour event to the target potion and its response back to the source.
+   * The source is only debited when the target accepts. Returns the reason if the target rejected the pour.
+   */
+  async pour(
+    source: EventChain,
+    target: EventChain,
+    pourEvent: CosmWasmEvent,
+    onProgress?: LogProgress
+  ): Promise<string | undefined> {
+    const info: MessageInfo = {
+      sender: this.eqty.address,
+      funds: [],
+    };
+    const sourceState = await this.eventChains.getStateDump(
+      source.id,
+      source.state.hex
+    );
+    const targetState = await this.eventChains.getStateDump(
+      target.id,
+      target.state.hex
+    );
+    if (!sourceState || !targetState) throw Error("State mismatch for pour");
+
+    // Both potions process the pour at the same time, so they agree on whether it expired
+    const time = Date.now();
+
+    const pourEventMsg = {
+      msg: {
+        attributes: pourEvent.attributes,
+        network: "",
+        event_type: pourEvent.type,
+      },
+    };
+
+    const { events: targetEvents, state: targetStateDump } = await this.rpc(
+      target.id
+    ).externalEvent(pourEventMsg, info, targetState, time, target.events.length);
+
+    // The source keeps the poured amount reserved until the target acknowledges or rejects the event
+    const rejectEvent = targetEvents.find(
+      (event) => event.type === "pour_reject"
+    );
+    const responseEvent: (CosmWasmEvent & { contract?: string }) | undefined =
+      rejectEvent ?? targetEvents.find((event) => event.type === "pour_ack");
+    if (!responseEvent) throw Error("No pour acknowledgement emitted");
+    responseEvent.contract = target.id;
+    if (rejectEvent) {
+      // A rejection isn't added to the target chain, it's the next event on the source chain
+      responseEvent.attributes.tx_hash = source.latestHash.hex;
+      responseEvent.attributes.log_index = String(source.events.length);
+    } else {
+      // The pour event is the next event on the target chain
+      responseEvent.attributes.tx_hash = target.latestHash.hex;
+      responseEvent.attributes.log_index = String(target.events.length);
+    }
+
+    const responseEventMsg = {
+      msg: {
+        attributes: responseEvent.attributes,
+        network: "",
+        event_type: responseEvent.type,
+      },
+    };
+
+    const { state: sourceStateDump } = await this.rpc(
+      source.id
+    ).externalEvent(
+      responseEventMsg,
+      info,
+      sourceState,
+      time,
+      source.events.length
+    );
+
+    await withProgress(onProgress)("signEvent", async () => {
+      if (!rejectEvent) {
+        await this.eqty.sign(
+          new Event({
+            "@context": "external_event_msg.json",
+            contract: source.id,
+            ...pourEvent,
+          }).addTo(target)
+        );
+      }
+      await this.eqty.sign(
+        new Event({
+          "@context": "external_event_msg.json",
+          ...responseEvent,
+        }).addTo(source)
+      );
+    });
+
+    await this.store(source, sourceStateDump);
+    if (!rejectEvent) await this.store(target, targetStateDump);
+    await this.submitAnchors(onProgress);
+
+    return rejectEvent?.attributes.reason;
+  }
+
   private async retryOperation<T>(
     operation: () => Promise<T>,
     maxRetries: number = 3,